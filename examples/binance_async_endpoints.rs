@@ -0,0 +1,34 @@
+use binance::asynchronous::api::*;
+use binance::asynchronous::futures::general::*;
+use binance::asynchronous::futures::market::*;
+use binance::futures::model::*;
+
+#[tokio::main]
+async fn main() {
+    let general: FuturesGeneral = Binance::new(None, None);
+    let market: FuturesMarket = Binance::new(None, None);
+
+    match general.get_server_time().await {
+        Ok(answer) => println!("Server Time: {}", answer.server_time),
+        Err(e) => println!("Error: {}", e),
+    }
+
+    // Both requests run concurrently on the same runtime
+    let (depth, klines) = tokio::join!(
+        market.get_depth("btcusdt"),
+        market.get_klines("btcusdt", "5m", 10, None, None)
+    );
+
+    match depth {
+        Ok(answer) => println!("Depth update ID: {:?}", answer.last_update_id),
+        Err(e) => println!("Error: {}", e),
+    }
+
+    match klines {
+        Ok(KlineSummaries::AllKlineSummaries(answer)) => match answer.first() {
+            Some(kline) => println!("First kline: {:?}", kline),
+            None => println!("No kline"),
+        },
+        Err(e) => println!("Error: {}", e),
+    }
+}
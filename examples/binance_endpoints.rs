@@ -4,10 +4,13 @@ use binance::account::*;
 use binance::market::*;
 use binance::errors::ErrorKind as BinanceLibErrorKind;
 
+// cargo run --example binance_endpoints [general|account|market_data]
 fn main() {
-    general();
-    //account();
-    //market_data();
+    match std::env::args().nth(1).as_deref() {
+        Some("account") => account(),
+        Some("market_data") => market_data(),
+        _ => general(),
+    }
 }
 
 fn general() {
@@ -68,22 +71,23 @@ fn account() {
         Err(e) => println!("Error: {}", e),
     }
 
-    match account.limit_buy("WTCETH", 10, 0.014000) {
+    // Prices are parsed, so they are f64 or Decimal depending on the `decimal` feature
+    match account.limit_buy("WTCETH", 10, "0.014".parse().unwrap(), "my_limit_buy".into()) {
         Ok(answer) => println!("{:?}", answer),
         Err(e) => println!("Error: {}", e),
     }
 
-    match account.market_buy("WTCETH", 5) {
+    match account.market_buy("WTCETH", 5, "my_market_buy".into()) {
         Ok(answer) => println!("{:?}", answer),
         Err(e) => println!("Error: {}", e),
     }
 
-    match account.limit_sell("WTCETH", 10, 0.035000) {
+    match account.limit_sell("WTCETH", 10, "0.035".parse().unwrap(), "my_limit_sell".into()) {
         Ok(answer) => println!("{:?}", answer),
         Err(e) => println!("Error: {}", e),
     }
 
-    match account.market_sell("WTCETH", 5) {
+    match account.market_sell("WTCETH", 5, "my_market_sell".into()) {
         Ok(answer) => println!("{:?}", answer),
         Err(e) => println!("Error: {}", e),
     }
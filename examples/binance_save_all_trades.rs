@@ -13,7 +13,7 @@ fn main() {
 fn save_all_trades_websocket() {
     struct WebSocketHandler {
         wrt: Writer<File>,
-    };
+    }
 
     impl WebSocketHandler {
         pub fn new(local_wrt: Writer<File>) -> Self {
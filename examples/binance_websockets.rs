@@ -3,13 +3,16 @@ use binance::userstream::*;
 use binance::websockets::*;
 use std::sync::atomic::{AtomicBool, Ordering};
 
+// cargo run --example binance_websockets [user_stream|user_stream_websocket|market_websocket|kline_websocket|all_trades_websocket|last_price]
 fn main() {
-    //user_stream();
-    //user_stream_websocket();
-    //market_websocket();
-    //kline_websocket();
-    //all_trades_websocket();
-    last_price();
+    match std::env::args().nth(1).as_deref() {
+        Some("user_stream") => user_stream(),
+        Some("user_stream_websocket") => user_stream_websocket(),
+        Some("market_websocket") => market_websocket(),
+        Some("kline_websocket") => kline_websocket(),
+        Some("all_trades_websocket") => all_trades_websocket(),
+        _ => last_price(),
+    }
 }
 
 fn user_stream() {
@@ -168,8 +171,8 @@ fn last_price() {
         if let WebsocketEvent::DayTicker(ticker_events) = event {
             for tick_event in ticker_events {
                 if tick_event.symbol == "BTCUSDT" {
-                    btcusdt = tick_event.average_price.parse().unwrap();
-                    let btcusdt_close: f32 = tick_event.current_close.parse().unwrap();
+                    btcusdt = tick_event.average_price.to_string().parse().unwrap();
+                    let btcusdt_close: f32 = tick_event.current_close.to_string().parse().unwrap();
                     println!("{} - {}", btcusdt, btcusdt_close);
 
                     if btcusdt_close as i32 == 7000 {
@@ -2,24 +2,16 @@ use crate::util::*;
 use crate::model::*;
 use crate::client::*;
 use crate::errors::*;
+use crate::symbolrules::SymbolRules;
 use std::collections::BTreeMap;
 use serde_json::from_str;
 
-static ORDER_TYPE_LIMIT: &str = "LIMIT";
-static ORDER_TYPE_MARKET: &str = "MARKET";
-static ORDER_SIDE_BUY: &str = "BUY";
-static ORDER_SIDE_SELL: &str = "SELL";
-static TIME_IN_FORCE_GTC: &str = "GTC";
-static TIME_IN_FORCE_GTX: &str = "GTX";
-static NEW_ORDER_RESP_TYPE_RESULT: &str = "RESULT";
-// static NEW_ORDER_RESP_TYPE_ACK: &str = "ACK";
-
-static API_V3_ORDER: &str = "/fapi/v1/order";
+pub(crate) static API_V3_ORDER: &str = "/fapi/v1/order";
 
 /// Endpoint for test orders.
 ///
 /// Orders issued to this endpoint are validated, but not sent into the matching engine.
-static API_V3_ORDER_TEST: &str = "/fapi/v1/order/test";
+pub(crate) static API_V3_ORDER_TEST: &str = "/fapi/v1/order/test";
 
 #[derive(Clone)]
 pub struct Account {
@@ -27,17 +19,6 @@ pub struct Account {
     pub recv_window: u64,
 }
 
-struct OrderRequest {
-    pub symbol: String,
-    pub qty: f64,
-    pub price: f64,
-    pub order_side: String,
-    pub order_type: String,
-    pub time_in_force: String,
-    pub new_order_resp_type: String,
-    pub new_client_order_id: String,
-}
-
 impl Account {
     // Account Information
     pub fn get_account_v2(&self) -> Result<AccountInformationV2> {
@@ -66,11 +47,7 @@ impl Account {
     where
         S: Into<String>,
     {
-
-        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
-        parameters.insert("symbol".into(), symbol.into());
-
-        let request = build_signed_request(parameters, self.recv_window)?;
+        let request = build_signed_request(symbol_parameters(symbol.into()), self.recv_window)?;
         let data = self.client.get_signed("/fapi/v2/positionRisk", &request)?;
         let futures_positions: Vec<FuturesPositionV2> = from_str(data.as_str())?;
 
@@ -101,10 +78,7 @@ impl Account {
     where
         S: Into<String>,
     {
-        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
-        parameters.insert("symbol".into(), symbol.into());
-
-        let request = build_signed_request(parameters, self.recv_window)?;
+        let request = build_signed_request(symbol_parameters(symbol.into()), self.recv_window)?;
         let data = self.client.get_signed("/fapi/v1/openOrders", &request)?;
         let order: Vec<Order> = from_str(data.as_str())?;
 
@@ -117,10 +91,7 @@ impl Account {
         S: Into<String>,
         T: Into<u64>,
     {
-        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
-        parameters.insert("symbol".into(), symbol.into());
-        parameters.insert("startTime".into(), start_time.into().to_string());
-
+        let parameters = income_parameters(symbol.into(), start_time.into());
         let request = build_signed_request(parameters, self.recv_window)?;
         let data = self.client.get_signed("/fapi/v1/income", &request)?;
         let incomes: Vec<FuturesIncome> = from_str(data.as_str())?;
@@ -144,9 +115,7 @@ impl Account {
     where
         S: Into<String>,
     {
-        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
-        parameters.insert("symbol".into(), symbol.into());
-        let request = build_signed_request(parameters, self.recv_window)?;
+        let request = build_signed_request(symbol_parameters(symbol.into()), self.recv_window)?;
         let data = self.client.delete_signed("/fapi/v1/allOpenOrders", &request)?;
         let resp: FuturesCancelAllResp = from_str(data.as_str())?;
 
@@ -161,10 +130,7 @@ impl Account {
     where
         S: Into<String>,
     {
-        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
-        parameters.insert("symbol".into(), symbol.into());
-        parameters.insert("orderId".into(), order_id.to_string());
-
+        let parameters = order_id_parameters(symbol.into(), order_id);
         let request = build_signed_request(parameters, self.recv_window)?;
         let data = self.client.get_signed(API_V3_ORDER, &request)?;
         let order: FuturesOrder = from_str(data.as_str())?;
@@ -179,10 +145,7 @@ impl Account {
     where
         S: Into<String>,
     {
-        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
-        parameters.insert("symbol".into(), symbol.into());
-        parameters.insert("orderId".into(), order_id.to_string());
-
+        let parameters = order_id_parameters(symbol.into(), order_id);
         let request = build_signed_request(parameters, self.recv_window)?;
         let data = self.client.get_signed(API_V3_ORDER_TEST, &request)?;
         let _: TestResponse = from_str(data.as_str())?;
@@ -191,131 +154,66 @@ impl Account {
     }
 
     // Place a LIMIT order - BUY
-    pub fn limit_buy<S, F>(&self, symbol: S, qty: F, price: f64, new_client_order_id: String) -> Result<FuturesTransaction>
+    pub fn limit_buy<S, F>(&self, symbol: S, qty: F, price: Num, new_client_order_id: String) -> Result<FuturesTransaction>
     where
         S: Into<String>,
-        F: Into<f64>,
+        F: Into<Num>,
     {
-        let buy: OrderRequest = OrderRequest {
-            symbol: symbol.into(),
-            qty: qty.into(),
-            price,
-            order_side: ORDER_SIDE_BUY.to_string(),
-            order_type: ORDER_TYPE_LIMIT.to_string(),
-            time_in_force: TIME_IN_FORCE_GTX.to_string(),
-            // new_order_resp_type: NEW_ORDER_RESP_TYPE_ACK.to_string(),
-            new_order_resp_type: NEW_ORDER_RESP_TYPE_RESULT.to_string(),
-            new_client_order_id: new_client_order_id,
-        };
-        let order = self.build_order(buy);
-        let request = build_signed_request(order, self.recv_window)?;
-        let data = self.client.post_signed(API_V3_ORDER, &request)?;
-        let transaction: FuturesTransaction = from_str(data.as_str())?;
+        let order =
+            limit_order(symbol.into(), OrderSide::Buy, qty.into(), price, new_client_order_id);
 
-        Ok(transaction)
+        self.place_order(&order)
     }
 
-    /// Place a test limit order - BUY
+    /// Place a test LIMIT order - BUY
     ///
     /// This order is sandboxed: it is validated, but not sent to the matching engine.
-    pub fn test_limit_buy<S, F>(&self, symbol: S, qty: F, price: f64, new_client_order_id: String) -> Result<()>
+    pub fn test_limit_buy<S, F>(&self, symbol: S, qty: F, price: Num, new_client_order_id: String) -> Result<()>
     where
         S: Into<String>,
-        F: Into<f64>,
+        F: Into<Num>,
     {
-        let buy: OrderRequest = OrderRequest {
-            symbol: symbol.into(),
-            qty: qty.into(),
-            price,
-            order_side: ORDER_SIDE_BUY.to_string(),
-            order_type: ORDER_TYPE_LIMIT.to_string(),
-            time_in_force: TIME_IN_FORCE_GTX.to_string(),
-            // new_order_resp_type: NEW_ORDER_RESP_TYPE_ACK.to_string(),
-            new_order_resp_type: NEW_ORDER_RESP_TYPE_RESULT.to_string(),
-            new_client_order_id: new_client_order_id,
-        };
-        let order = self.build_order(buy);
-        let request = build_signed_request(order, self.recv_window)?;
-        let data = self.client.post_signed(API_V3_ORDER_TEST, &request)?;
-        let _: TestResponse = from_str(data.as_str())?;
+        let order =
+            limit_order(symbol.into(), OrderSide::Buy, qty.into(), price, new_client_order_id);
 
-        Ok(())
+        self.test_order(&order)
     }
 
     // Place a LIMIT order - SELL
-    pub fn limit_sell<S, F>(&self, symbol: S, qty: F, price: f64, new_client_order_id: String) -> Result<FuturesTransaction>
+    pub fn limit_sell<S, F>(&self, symbol: S, qty: F, price: Num, new_client_order_id: String) -> Result<FuturesTransaction>
     where
         S: Into<String>,
-        F: Into<f64>,
+        F: Into<Num>,
     {
-        let sell: OrderRequest = OrderRequest {
-            symbol: symbol.into(),
-            qty: qty.into(),
-            price,
-            order_side: ORDER_SIDE_SELL.to_string(),
-            order_type: ORDER_TYPE_LIMIT.to_string(),
-            time_in_force: TIME_IN_FORCE_GTX.to_string(),
-            // new_order_resp_type: NEW_ORDER_RESP_TYPE_ACK.to_string(),
-            new_order_resp_type: NEW_ORDER_RESP_TYPE_RESULT.to_string(),
-            new_client_order_id: new_client_order_id,
-        };
-        let order = self.build_order(sell);
-        let request = build_signed_request(order, self.recv_window)?;
-        let data = self.client.post_signed(API_V3_ORDER, &request)?;
-        let transaction: FuturesTransaction = from_str(data.as_str())?;
+        let order =
+            limit_order(symbol.into(), OrderSide::Sell, qty.into(), price, new_client_order_id);
 
-        Ok(transaction)
+        self.place_order(&order)
     }
 
     /// Place a test LIMIT order - SELL
     ///
     /// This order is sandboxed: it is validated, but not sent to the matching engine.
-    pub fn test_limit_sell<S, F>(&self, symbol: S, qty: F, price: f64, new_client_order_id: String) -> Result<()>
+    pub fn test_limit_sell<S, F>(&self, symbol: S, qty: F, price: Num, new_client_order_id: String) -> Result<()>
     where
         S: Into<String>,
-        F: Into<f64>,
+        F: Into<Num>,
     {
-        let sell: OrderRequest = OrderRequest {
-            symbol: symbol.into(),
-            qty: qty.into(),
-            price,
-            order_side: ORDER_SIDE_SELL.to_string(),
-            order_type: ORDER_TYPE_LIMIT.to_string(),
-            time_in_force: TIME_IN_FORCE_GTX.to_string(),
-            // new_order_resp_type: NEW_ORDER_RESP_TYPE_ACK.to_string(),
-            new_order_resp_type: NEW_ORDER_RESP_TYPE_RESULT.to_string(),
-            new_client_order_id: new_client_order_id,
-        };
-        let order = self.build_order(sell);
-        let request = build_signed_request(order, self.recv_window)?;
-        let data = self.client.post_signed(API_V3_ORDER_TEST, &request)?;
-        let _: TestResponse = from_str(data.as_str())?;
+        let order =
+            limit_order(symbol.into(), OrderSide::Sell, qty.into(), price, new_client_order_id);
 
-        Ok(())
+        self.test_order(&order)
     }
 
     // Place a MARKET order - BUY
     pub fn market_buy<S, F>(&self, symbol: S, qty: F, new_client_order_id: String) -> Result<FuturesTransaction>
     where
         S: Into<String>,
-        F: Into<f64>,
+        F: Into<Num>,
     {
-        let buy: OrderRequest = OrderRequest {
-            symbol: symbol.into(),
-            qty: qty.into(),
-            price: 0.0,
-            order_side: ORDER_SIDE_BUY.to_string(),
-            order_type: ORDER_TYPE_MARKET.to_string(),
-            time_in_force: TIME_IN_FORCE_GTC.to_string(),
-            new_order_resp_type: NEW_ORDER_RESP_TYPE_RESULT.to_string(),
-            new_client_order_id: new_client_order_id,
-        };
-        let order = self.build_order(buy);
-        let request = build_signed_request(order, self.recv_window)?;
-        let data = self.client.post_signed(API_V3_ORDER, &request)?;
-        let transaction: FuturesTransaction = from_str(data.as_str())?;
+        let order = market_order(symbol.into(), OrderSide::Buy, qty.into(), new_client_order_id);
 
-        Ok(transaction)
+        self.place_order(&order)
     }
 
     /// Place a test MARKET order - BUY
@@ -324,48 +222,22 @@ impl Account {
     pub fn test_market_buy<S, F>(&self, symbol: S, qty: F, new_client_order_id: String) -> Result<()>
     where
         S: Into<String>,
-        F: Into<f64>,
+        F: Into<Num>,
     {
-        let buy: OrderRequest = OrderRequest {
-            symbol: symbol.into(),
-            qty: qty.into(),
-            price: 0.0,
-            order_side: ORDER_SIDE_BUY.to_string(),
-            order_type: ORDER_TYPE_MARKET.to_string(),
-            time_in_force: TIME_IN_FORCE_GTC.to_string(),
-            new_order_resp_type: NEW_ORDER_RESP_TYPE_RESULT.to_string(),
-            new_client_order_id: new_client_order_id,
-        };
-        let order = self.build_order(buy);
-        let request = build_signed_request(order, self.recv_window)?;
-        let data = self.client.post_signed(API_V3_ORDER_TEST, &request)?;
-        let _: TestResponse = from_str(data.as_str())?;
+        let order = market_order(symbol.into(), OrderSide::Buy, qty.into(), new_client_order_id);
 
-        Ok(())
+        self.test_order(&order)
     }
 
     // Place a MARKET order - SELL
     pub fn market_sell<S, F>(&self, symbol: S, qty: F, new_client_order_id: String) -> Result<FuturesTransaction>
     where
         S: Into<String>,
-        F: Into<f64>,
+        F: Into<Num>,
     {
-        let sell: OrderRequest = OrderRequest {
-            symbol: symbol.into(),
-            qty: qty.into(),
-            price: 0.0,
-            order_side: ORDER_SIDE_SELL.to_string(),
-            order_type: ORDER_TYPE_MARKET.to_string(),
-            time_in_force: TIME_IN_FORCE_GTC.to_string(),
-            new_order_resp_type: NEW_ORDER_RESP_TYPE_RESULT.to_string(),
-            new_client_order_id: new_client_order_id,
-        };
-        let order = self.build_order(sell);
-        let request = build_signed_request(order, self.recv_window)?;
-        let data = self.client.post_signed(API_V3_ORDER, &request)?;
-        let transaction: FuturesTransaction = from_str(data.as_str())?;
+        let order = market_order(symbol.into(), OrderSide::Sell, qty.into(), new_client_order_id);
 
-        Ok(transaction)
+        self.place_order(&order)
     }
 
     /// Place a test MARKET order - SELL
@@ -374,20 +246,36 @@ impl Account {
     pub fn test_market_sell<S, F>(&self, symbol: S, qty: F, new_client_order_id: String) -> Result<()>
     where
         S: Into<String>,
-        F: Into<f64>,
+        F: Into<Num>,
     {
-        let sell: OrderRequest = OrderRequest {
-            symbol: symbol.into(),
-            qty: qty.into(),
-            price: 0.0,
-            order_side: ORDER_SIDE_SELL.to_string(),
-            order_type: ORDER_TYPE_MARKET.to_string(),
-            time_in_force: TIME_IN_FORCE_GTC.to_string(),
-            new_order_resp_type: NEW_ORDER_RESP_TYPE_RESULT.to_string(),
-            new_client_order_id: new_client_order_id,
-        };
-        let order = self.build_order(sell);
-        let request = build_signed_request(order, self.recv_window)?;
+        let order = market_order(symbol.into(), OrderSide::Sell, qty.into(), new_client_order_id);
+
+        self.test_order(&order)
+    }
+
+    /// Place an order of any type, see `NewOrder`
+    pub fn place_order(&self, order: &NewOrder) -> Result<FuturesTransaction> {
+        let request = build_signed_request(order.to_parameters(), self.recv_window)?;
+        let data = self.client.post_signed(API_V3_ORDER, &request)?;
+        let transaction: FuturesTransaction = from_str(data.as_str())?;
+
+        Ok(transaction)
+    }
+
+    /// Place an order after checking it locally against the symbol filters
+    ///
+    /// Returns `ErrorKind::InvalidOrder` without sending the order if a filter is not met.
+    pub fn place_validated_order(&self, order: &NewOrder, rules: &SymbolRules) -> Result<FuturesTransaction> {
+        rules.validate(order)?;
+
+        self.place_order(order)
+    }
+
+    /// Place a test order of any type
+    ///
+    /// This order is sandboxed: it is validated, but not sent to the matching engine.
+    pub fn test_order(&self, order: &NewOrder) -> Result<()> {
+        let request = build_signed_request(order.to_parameters(), self.recv_window)?;
         let data = self.client.post_signed(API_V3_ORDER_TEST, &request)?;
         let _: TestResponse = from_str(data.as_str())?;
 
@@ -399,11 +287,7 @@ impl Account {
     where
         S: Into<String>,
     {
-        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
-        parameters.insert("symbol".into(), symbol.into());
-        // parameters.insert("orderId".into(), order_id.to_string());
-        parameters.insert("origClientOrderId".into(), orig_client_order_id.to_string());
-
+        let parameters = client_order_id_parameters(symbol.into(), orig_client_order_id);
         let request = build_signed_request(parameters, self.recv_window)?;
         let data = self.client.delete_signed(API_V3_ORDER, &request)?;
         let order_canceled: OrderCanceled = from_str(data.as_str())?;
@@ -418,11 +302,7 @@ impl Account {
     where
         S: Into<String>,
     {
-        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
-        parameters.insert("symbol".into(), symbol.into());
-        // parameters.insert("orderId".into(), order_id.to_string());
-        parameters.insert("origClientOrderId".into(), orig_client_order_id.to_string());
-
+        let parameters = client_order_id_parameters(symbol.into(), orig_client_order_id);
         let request = build_signed_request(parameters, self.recv_window)?;
         let data = self.client.delete_signed(API_V3_ORDER_TEST, &request)?;
         let _: TestResponse = from_str(data.as_str())?;
@@ -435,31 +315,473 @@ impl Account {
     where
         S: Into<String>,
     {
-        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
-        parameters.insert("symbol".into(), symbol.into());
-
-        let request = build_signed_request(parameters, self.recv_window)?;
+        let request = build_signed_request(symbol_parameters(symbol.into()), self.recv_window)?;
         let data = self.client.get_signed("/fapi/v1/myTrades", &request)?;
         let trade_history: Vec<TradeHistory> = from_str(data.as_str())?;
 
         Ok(trade_history)
     }
+}
 
-    fn build_order(&self, order: OrderRequest) -> BTreeMap<String, String> {
-        let mut order_parameters: BTreeMap<String, String> = BTreeMap::new();
+// LIMIT order of `limit_buy` and `limit_sell`, rejected if it would take liquidity
+pub(crate) fn limit_order(
+    symbol: String, side: OrderSide, qty: Num, price: Num, new_client_order_id: String,
+) -> NewOrder {
+    NewOrder::limit(symbol, side, qty, price)
+        .set_time_in_force(TimeInForce::Gtx)
+        .set_new_client_order_id(new_client_order_id)
+}
+
+// MARKET order of `market_buy` and `market_sell`
+pub(crate) fn market_order(
+    symbol: String, side: OrderSide, qty: Num, new_client_order_id: String,
+) -> NewOrder {
+    NewOrder::market(symbol, side, qty).set_new_client_order_id(new_client_order_id)
+}
 
-        order_parameters.insert("symbol".into(), order.symbol);
-        order_parameters.insert("side".into(), order.order_side);
-        order_parameters.insert("type".into(), order.order_type);
-        order_parameters.insert("quantity".into(), order.qty.to_string());
-        order_parameters.insert("newOrderRespType".into(), order.new_order_resp_type);
-        order_parameters.insert("newClientOrderId".into(), order.new_client_order_id);
+/// New order for `Account::place_order` and `Account::test_order`.
+///
+/// Start from the constructor of the order type, then set the optional parameters.
+#[derive(Debug, Clone)]
+pub struct NewOrder {
+    symbol: String,
+    side: OrderSide,
+    order_type: OrderType,
+    position_side: Option<PositionSide>,
+    time_in_force: Option<TimeInForce>,
+    quantity: Option<Num>,
+    price: Option<Num>,
+    stop_price: Option<Num>,
+    reduce_only: Option<bool>,
+    close_position: Option<bool>,
+    activation_price: Option<Num>,
+    callback_rate: Option<Num>,
+    working_type: Option<WorkingType>,
+    price_protect: Option<bool>,
+    new_client_order_id: Option<String>,
+    new_order_resp_type: String,
+}
 
-        if order.price != 0.0 {
-            order_parameters.insert("price".into(), order.price.to_string());
-            order_parameters.insert("timeInForce".into(), order.time_in_force);
+impl NewOrder {
+    pub fn new<S: Into<String>>(symbol: S, side: OrderSide, order_type: OrderType) -> Self {
+        NewOrder {
+            symbol: symbol.into(),
+            side,
+            order_type,
+            position_side: None,
+            time_in_force: None,
+            quantity: None,
+            price: None,
+            stop_price: None,
+            reduce_only: None,
+            close_position: None,
+            activation_price: None,
+            callback_rate: None,
+            working_type: None,
+            price_protect: None,
+            new_client_order_id: None,
+            new_order_resp_type: NEW_ORDER_RESP_TYPE_RESULT.into(),
         }
+    }
+
+    // LIMIT order, good till cancel unless set otherwise
+    pub fn limit<S, Q, P>(symbol: S, side: OrderSide, qty: Q, price: P) -> Self
+    where
+        S: Into<String>,
+        Q: Into<Num>,
+        P: Into<Num>,
+    {
+        NewOrder::new(symbol, side, OrderType::Limit)
+            .set_quantity(qty)
+            .set_price(price)
+            .set_time_in_force(TimeInForce::Gtc)
+    }
+
+    pub fn market<S, Q>(symbol: S, side: OrderSide, qty: Q) -> Self
+    where
+        S: Into<String>,
+        Q: Into<Num>,
+    {
+        NewOrder::new(symbol, side, OrderType::Market).set_quantity(qty)
+    }
+
+    // STOP order: LIMIT order at `price` once `stop_price` is reached
+    pub fn stop<S, Q, P, T>(symbol: S, side: OrderSide, qty: Q, price: P, stop_price: T) -> Self
+    where
+        S: Into<String>,
+        Q: Into<Num>,
+        P: Into<Num>,
+        T: Into<Num>,
+    {
+        NewOrder::new(symbol, side, OrderType::Stop)
+            .set_quantity(qty)
+            .set_price(price)
+            .set_stop_price(stop_price)
+            .set_time_in_force(TimeInForce::Gtc)
+    }
+
+    // STOP_MARKET order, set either a quantity or close_position
+    pub fn stop_market<S, T>(symbol: S, side: OrderSide, stop_price: T) -> Self
+    where
+        S: Into<String>,
+        T: Into<Num>,
+    {
+        NewOrder::new(symbol, side, OrderType::StopMarket).set_stop_price(stop_price)
+    }
+
+    // TAKE_PROFIT order: LIMIT order at `price` once `stop_price` is reached
+    pub fn take_profit<S, Q, P, T>(
+        symbol: S, side: OrderSide, qty: Q, price: P, stop_price: T,
+    ) -> Self
+    where
+        S: Into<String>,
+        Q: Into<Num>,
+        P: Into<Num>,
+        T: Into<Num>,
+    {
+        NewOrder::new(symbol, side, OrderType::TakeProfit)
+            .set_quantity(qty)
+            .set_price(price)
+            .set_stop_price(stop_price)
+            .set_time_in_force(TimeInForce::Gtc)
+    }
+
+    // TAKE_PROFIT_MARKET order, set either a quantity or close_position
+    pub fn take_profit_market<S, T>(symbol: S, side: OrderSide, stop_price: T) -> Self
+    where
+        S: Into<String>,
+        T: Into<Num>,
+    {
+        NewOrder::new(symbol, side, OrderType::TakeProfitMarket).set_stop_price(stop_price)
+    }
+
+    // TRAILING_STOP_MARKET order, `callback_rate` in percent (0.1 - 5)
+    pub fn trailing_stop_market<S, Q, R>(
+        symbol: S, side: OrderSide, qty: Q, callback_rate: R,
+    ) -> Self
+    where
+        S: Into<String>,
+        Q: Into<Num>,
+        R: Into<Num>,
+    {
+        NewOrder::new(symbol, side, OrderType::TrailingStopMarket)
+            .set_quantity(qty)
+            .set_callback_rate(callback_rate)
+    }
+
+    // Required in hedge mode
+    pub fn set_position_side(mut self, position_side: PositionSide) -> Self {
+        self.position_side = Some(position_side);
+        self
+    }
+
+    pub fn set_time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.time_in_force = Some(time_in_force);
+        self
+    }
+
+    pub fn set_quantity<F: Into<Num>>(mut self, quantity: F) -> Self {
+        self.quantity = Some(quantity.into());
+        self
+    }
+
+    pub fn set_price<F: Into<Num>>(mut self, price: F) -> Self {
+        self.price = Some(price.into());
+        self
+    }
+
+    pub fn set_stop_price<F: Into<Num>>(mut self, stop_price: F) -> Self {
+        self.stop_price = Some(stop_price.into());
+        self
+    }
+
+    pub fn set_reduce_only(mut self, reduce_only: bool) -> Self {
+        self.reduce_only = Some(reduce_only);
+        self
+    }
+
+    // Close the whole position, only with STOP_MARKET and TAKE_PROFIT_MARKET
+    pub fn set_close_position(mut self, close_position: bool) -> Self {
+        self.close_position = Some(close_position);
+        self
+    }
+
+    // TRAILING_STOP_MARKET only, defaults to the latest price
+    pub fn set_activation_price<F: Into<Num>>(mut self, activation_price: F) -> Self {
+        self.activation_price = Some(activation_price.into());
+        self
+    }
+
+    pub fn set_callback_rate<F: Into<Num>>(mut self, callback_rate: F) -> Self {
+        self.callback_rate = Some(callback_rate.into());
+        self
+    }
+
+    // Price triggering `stop_price`, CONTRACT_PRICE by default
+    pub fn set_working_type(mut self, working_type: WorkingType) -> Self {
+        self.working_type = Some(working_type);
+        self
+    }
+
+    pub fn set_price_protect(mut self, price_protect: bool) -> Self {
+        self.price_protect = Some(price_protect);
+        self
+    }
+
+    pub fn set_new_client_order_id<S: Into<String>>(mut self, new_client_order_id: S) -> Self {
+        self.new_client_order_id = Some(new_client_order_id.into());
+        self
+    }
+
+    // ACK or RESULT (default)
+    pub fn set_new_order_resp_type<S: Into<String>>(mut self, new_order_resp_type: S) -> Self {
+        self.new_order_resp_type = new_order_resp_type.into();
+        self
+    }
+
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn side(&self) -> &OrderSide {
+        &self.side
+    }
+
+    pub fn order_type(&self) -> &OrderType {
+        &self.order_type
+    }
+
+    pub fn quantity(&self) -> Option<Num> {
+        self.quantity
+    }
+
+    pub fn price(&self) -> Option<Num> {
+        self.price
+    }
+
+    pub fn stop_price(&self) -> Option<Num> {
+        self.stop_price
+    }
+
+    pub fn activation_price(&self) -> Option<Num> {
+        self.activation_price
+    }
+
+    pub fn close_position(&self) -> bool {
+        self.close_position.unwrap_or(false)
+    }
+
+    pub(crate) fn to_parameters(&self) -> BTreeMap<String, String> {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+
+        parameters.insert("symbol".into(), self.symbol.clone());
+        parameters.insert("side".into(), self.side.to_string());
+        parameters.insert("type".into(), self.order_type.to_string());
+        parameters.insert("newOrderRespType".into(), self.new_order_resp_type.clone());
+
+        if let Some(ref position_side) = self.position_side {
+            parameters.insert("positionSide".into(), position_side.to_string());
+        }
+        if let Some(ref time_in_force) = self.time_in_force {
+            parameters.insert("timeInForce".into(), time_in_force.to_string());
+        }
+        if let Some(quantity) = self.quantity {
+            parameters.insert("quantity".into(), quantity.to_string());
+        }
+        if let Some(price) = self.price {
+            parameters.insert("price".into(), price.to_string());
+        }
+        if let Some(stop_price) = self.stop_price {
+            parameters.insert("stopPrice".into(), stop_price.to_string());
+        }
+        if let Some(reduce_only) = self.reduce_only {
+            parameters.insert("reduceOnly".into(), reduce_only.to_string());
+        }
+        if let Some(close_position) = self.close_position {
+            parameters.insert("closePosition".into(), close_position.to_string());
+        }
+        if let Some(activation_price) = self.activation_price {
+            parameters.insert("activationPrice".into(), activation_price.to_string());
+        }
+        if let Some(callback_rate) = self.callback_rate {
+            parameters.insert("callbackRate".into(), callback_rate.to_string());
+        }
+        if let Some(ref working_type) = self.working_type {
+            parameters.insert("workingType".into(), working_type.to_string());
+        }
+        if let Some(price_protect) = self.price_protect {
+            parameters.insert("priceProtect".into(), price_protect.to_string());
+        }
+        if let Some(ref new_client_order_id) = self.new_client_order_id {
+            parameters.insert("newClientOrderId".into(), new_client_order_id.clone());
+        }
+
+        parameters
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn num(value: &str) -> Num {
+        value.parse().unwrap()
+    }
+
+    fn parameters(order: NewOrder, expected: &[(&str, &str)]) {
+        let expected: BTreeMap<String, String> = expected
+            .iter()
+            .map(|&(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+
+        assert_eq!(order.to_parameters(), expected);
+    }
+
+    #[test]
+    fn limit_parameters() {
+        parameters(
+            NewOrder::limit("BTCUSDT", OrderSide::Buy, num("0.001"), num("30000.1")),
+            &[
+                ("symbol", "BTCUSDT"),
+                ("side", "BUY"),
+                ("type", "LIMIT"),
+                ("newOrderRespType", "RESULT"),
+                ("timeInForce", "GTC"),
+                ("quantity", "0.001"),
+                ("price", "30000.1"),
+            ],
+        );
+    }
+
+    #[test]
+    fn integer_amounts_parameters() {
+        parameters(
+            NewOrder::limit("BTCUSDT", OrderSide::Buy, 2, 20000).set_stop_price(19990),
+            &[
+                ("symbol", "BTCUSDT"),
+                ("side", "BUY"),
+                ("type", "LIMIT"),
+                ("newOrderRespType", "RESULT"),
+                ("timeInForce", "GTC"),
+                ("quantity", "2"),
+                ("price", "20000"),
+                ("stopPrice", "19990"),
+            ],
+        );
+    }
+
+    #[test]
+    fn market_parameters() {
+        parameters(
+            NewOrder::market("BTCUSDT", OrderSide::Sell, num("2"))
+                .set_reduce_only(true)
+                .set_position_side(PositionSide::Long)
+                .set_new_order_resp_type("ACK"),
+            &[
+                ("symbol", "BTCUSDT"),
+                ("side", "SELL"),
+                ("type", "MARKET"),
+                ("newOrderRespType", "ACK"),
+                ("quantity", "2"),
+                ("reduceOnly", "true"),
+                ("positionSide", "LONG"),
+            ],
+        );
+    }
+
+    #[test]
+    fn stop_parameters() {
+        parameters(
+            NewOrder::stop("BTCUSDT", OrderSide::Sell, num("1"), num("29000"), num("29500"))
+                .set_time_in_force(TimeInForce::Gtx),
+            &[
+                ("symbol", "BTCUSDT"),
+                ("side", "SELL"),
+                ("type", "STOP"),
+                ("newOrderRespType", "RESULT"),
+                ("timeInForce", "GTX"),
+                ("quantity", "1"),
+                ("price", "29000"),
+                ("stopPrice", "29500"),
+            ],
+        );
+    }
+
+    #[test]
+    fn stop_market_parameters() {
+        parameters(
+            NewOrder::stop_market("BTCUSDT", OrderSide::Sell, num("29000"))
+                .set_close_position(true)
+                .set_working_type(WorkingType::MarkPrice)
+                .set_price_protect(true),
+            &[
+                ("symbol", "BTCUSDT"),
+                ("side", "SELL"),
+                ("type", "STOP_MARKET"),
+                ("newOrderRespType", "RESULT"),
+                ("stopPrice", "29000"),
+                ("closePosition", "true"),
+                ("workingType", "MARK_PRICE"),
+                ("priceProtect", "true"),
+            ],
+        );
+    }
+
+    #[test]
+    fn take_profit_parameters() {
+        parameters(
+            NewOrder::take_profit("ETHUSDT", OrderSide::Buy, num("0.5"), num("1500"), num("1490"))
+                .set_reduce_only(false),
+            &[
+                ("symbol", "ETHUSDT"),
+                ("side", "BUY"),
+                ("type", "TAKE_PROFIT"),
+                ("newOrderRespType", "RESULT"),
+                ("timeInForce", "GTC"),
+                ("quantity", "0.5"),
+                ("price", "1500"),
+                ("stopPrice", "1490"),
+                ("reduceOnly", "false"),
+            ],
+        );
+    }
+
+    #[test]
+    fn take_profit_market_parameters() {
+        parameters(
+            NewOrder::take_profit_market("ETHUSDT", OrderSide::Sell, num("2100"))
+                .set_quantity(num("3"))
+                .set_price_protect(false)
+                .set_close_position(false),
+            &[
+                ("symbol", "ETHUSDT"),
+                ("side", "SELL"),
+                ("type", "TAKE_PROFIT_MARKET"),
+                ("newOrderRespType", "RESULT"),
+                ("stopPrice", "2100"),
+                ("quantity", "3"),
+                ("priceProtect", "false"),
+                ("closePosition", "false"),
+            ],
+        );
+    }
 
-        order_parameters
+    #[test]
+    fn trailing_stop_market_parameters() {
+        parameters(
+            NewOrder::trailing_stop_market("BTCUSDT", OrderSide::Sell, num("0.01"), num("1"))
+                .set_activation_price(num("35000"))
+                .set_new_client_order_id("my_trailing_stop"),
+            &[
+                ("symbol", "BTCUSDT"),
+                ("side", "SELL"),
+                ("type", "TRAILING_STOP_MARKET"),
+                ("newOrderRespType", "RESULT"),
+                ("quantity", "0.01"),
+                ("callbackRate", "1"),
+                ("activationPrice", "35000"),
+                ("newClientOrderId", "my_trailing_stop"),
+            ],
+        );
     }
 }
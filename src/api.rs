@@ -5,46 +5,87 @@ use crate::futures::general::*;
 use crate::futures::market::*;
 use crate::userstream::*;
 use crate::client::*;
-
-static API_HOST: &str = "https://fapi.binance.com";
-static FAPI_HOST: &str = "https://fapi.binance.com";
+use crate::config::*;
+use crate::errors::*;
 
 //#[derive(Clone)]
-pub trait Binance {
-    fn new(api_key: Option<String>, secret_key: Option<String>) -> Self;
+pub trait Binance: Sized {
+    fn new(api_key: Option<String>, secret_key: Option<String>) -> Self {
+        Self::new_with_config(api_key, secret_key, &Config::default())
+    }
+
+    fn new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Self {
+        let client = Client::new_with_config(
+            api_key,
+            secret_key,
+            config.rest_api_endpoint.clone(),
+            config,
+        );
+
+        Self::new_with_client(&client, config)
+    }
+
+    /// Like `new_with_config`, returning an error instead of panicking if the HTTP client
+    /// cannot be built.
+    fn try_new_with_config(
+        api_key: Option<String>, secret_key: Option<String>, config: &Config,
+    ) -> Result<Self> {
+        let client = Client::try_new_with_config(
+            api_key,
+            secret_key,
+            config.rest_api_endpoint.clone(),
+            config,
+        )?;
+
+        Ok(Self::new_with_client(&client, config))
+    }
+
+    /// Builds the API with RSA or Ed25519 request signing instead of HMAC.
+    fn new_with_signer<S: Signer + 'static>(
+        api_key: Option<String>, signer: S, config: &Config,
+    ) -> Self {
+        let client =
+            Client::new_with_config(api_key, None, config.rest_api_endpoint.clone(), config)
+                .with_signer(signer);
+
+        Self::new_with_client(&client, config)
+    }
+
+    /// Builds the API on top of an existing client, sharing its connection pool.
+    fn new_with_client(client: &Client, config: &Config) -> Self;
 }
 
 impl Binance for General {
-    fn new(api_key: Option<String>, secret_key: Option<String>) -> General {
+    fn new_with_client(client: &Client, config: &Config) -> General {
         General {
-            client: Client::new(api_key, secret_key, API_HOST.to_string()),
+            client: client.with_host(config.rest_api_endpoint.as_str()),
         }
     }
 }
 
 impl Binance for Account {
-    fn new(api_key: Option<String>, secret_key: Option<String>) -> Account {
+    fn new_with_client(client: &Client, config: &Config) -> Account {
         Account {
-            client: Client::new(api_key, secret_key, API_HOST.to_string()),
-            recv_window: 5000,
+            client: client.with_host(config.rest_api_endpoint.as_str()),
+            recv_window: config.recv_window,
         }
     }
 }
 
 impl Binance for Market {
-    fn new(api_key: Option<String>, secret_key: Option<String>) -> Market {
+    fn new_with_client(client: &Client, config: &Config) -> Market {
         Market {
-            client: Client::new(api_key, secret_key, API_HOST.to_string()),
-            recv_window: 5000,
+            client: client.with_host(config.rest_api_endpoint.as_str()),
+            recv_window: config.recv_window,
         }
     }
 }
 
 impl Binance for UserStream {
-    fn new(api_key: Option<String>, secret_key: Option<String>) -> UserStream {
+    fn new_with_client(client: &Client, config: &Config) -> UserStream {
         UserStream {
-            client: Client::new(api_key, secret_key, FAPI_HOST.to_string()),
-            recv_window: 5000,
+            client: client.with_host(config.futures_rest_api_endpoint.as_str()),
+            recv_window: config.recv_window,
         }
     }
 }
@@ -54,18 +95,18 @@ impl Binance for UserStream {
 // *****************************************************
 
 impl Binance for FuturesGeneral {
-    fn new(api_key: Option<String>, secret_key: Option<String>) -> FuturesGeneral {
+    fn new_with_client(client: &Client, config: &Config) -> FuturesGeneral {
         FuturesGeneral {
-            client: Client::new(api_key, secret_key, FAPI_HOST.to_string()),
+            client: client.with_host(config.futures_rest_api_endpoint.as_str()),
         }
     }
 }
 
 impl Binance for FuturesMarket {
-    fn new(api_key: Option<String>, secret_key: Option<String>) -> FuturesMarket {
+    fn new_with_client(client: &Client, config: &Config) -> FuturesMarket {
         FuturesMarket {
-            client: Client::new(api_key, secret_key, FAPI_HOST.to_string()),
-            recv_window: 5000,
+            client: client.with_host(config.futures_rest_api_endpoint.as_str()),
+            recv_window: config.recv_window,
         }
     }
 }
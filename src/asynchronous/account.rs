@@ -0,0 +1,306 @@
+//! Async version of `account`, where the test orders are documented.
+
+use crate::util::*;
+use crate::model::*;
+use crate::account::{limit_order, market_order, NewOrder, API_V3_ORDER, API_V3_ORDER_TEST};
+use crate::asynchronous::client::*;
+use crate::errors::*;
+use crate::symbolrules::SymbolRules;
+use std::collections::BTreeMap;
+use serde_json::from_str;
+
+#[derive(Clone)]
+pub struct Account {
+    pub client: Client,
+    pub recv_window: u64,
+}
+
+impl Account {
+    // Account Information
+    pub async fn get_account_v2(&self) -> Result<AccountInformationV2> {
+        let parameters: BTreeMap<String, String> = BTreeMap::new();
+
+        let request = build_signed_request(parameters, self.recv_window)?;
+        let data = self.client.get_signed("/fapi/v2/account", &request).await?;
+        let account_info: AccountInformationV2 = from_str(data.as_str())?;
+
+        Ok(account_info)
+    }
+
+    // Account Information
+    pub async fn get_account(&self) -> Result<AccountInformation> {
+        let parameters: BTreeMap<String, String> = BTreeMap::new();
+
+        let request = build_signed_request(parameters, self.recv_window)?;
+        let data = self.client.get_signed("/fapi/v1/account", &request).await?;
+        let account_info: AccountInformation = from_str(data.as_str())?;
+
+        Ok(account_info)
+    }
+
+    // Account Information
+    pub async fn get_positions<S>(&self, symbol: S) -> Result<Vec<FuturesPositionV2>>
+    where
+        S: Into<String>,
+    {
+        let request = build_signed_request(symbol_parameters(symbol.into()), self.recv_window)?;
+        let data = self.client.get_signed("/fapi/v2/positionRisk", &request).await?;
+        let futures_positions: Vec<FuturesPositionV2> = from_str(data.as_str())?;
+
+        Ok(futures_positions)
+    }
+
+    // Balance for ONE Asset
+    pub async fn get_balance<S>(&self, asset: S) -> Result<Balance>
+    where
+        S: Into<String>,
+    {
+        match self.get_account().await {
+            Ok(account) => {
+                let cmp_asset = asset.into();
+                for balance in account.balances {
+                    if balance.asset == cmp_asset {
+                        return Ok(balance);
+                    }
+                }
+                bail!("Asset not found");
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    // Current open orders for ONE symbol
+    pub async fn get_open_orders<S>(&self, symbol: S) -> Result<Vec<Order>>
+    where
+        S: Into<String>,
+    {
+        let request = build_signed_request(symbol_parameters(symbol.into()), self.recv_window)?;
+        let data = self.client.get_signed("/fapi/v1/openOrders", &request).await?;
+        let order: Vec<Order> = from_str(data.as_str())?;
+
+        Ok(order)
+    }
+
+    // Income since
+    pub async fn get_incomes<S, T>(&self, symbol: S, start_time: T) -> Result<Vec<FuturesIncome>>
+    where
+        S: Into<String>,
+        T: Into<u64>,
+    {
+        let parameters = income_parameters(symbol.into(), start_time.into());
+        let request = build_signed_request(parameters, self.recv_window)?;
+        let data = self.client.get_signed("/fapi/v1/income", &request).await?;
+        let incomes: Vec<FuturesIncome> = from_str(data.as_str())?;
+
+        Ok(incomes)
+    }
+
+    // All current open orders
+    pub async fn get_all_open_orders(&self) -> Result<Vec<Order>> {
+        let parameters: BTreeMap<String, String> = BTreeMap::new();
+
+        let request = build_signed_request(parameters, self.recv_window)?;
+        let data = self.client.get_signed("/fapi/v1/openOrders", &request).await?;
+        let order: Vec<Order> = from_str(data.as_str())?;
+
+        Ok(order)
+    }
+
+    // Cancel all open orders for ONE symbol
+    pub async fn cancel_all_open_orders<S>(&self, symbol: S) -> Result<FuturesCancelAllResp>
+    where
+        S: Into<String>,
+    {
+        let request = build_signed_request(symbol_parameters(symbol.into()), self.recv_window)?;
+        let data = self.client.delete_signed("/fapi/v1/allOpenOrders", &request).await?;
+        let resp: FuturesCancelAllResp = from_str(data.as_str())?;
+
+        match resp.code {
+            200 => Ok(resp),
+            _ => bail!("cancel_all_open_orders code != 200"),
+        }
+    }
+
+    // Check an order's status
+    pub async fn order_status<S>(&self, symbol: S, order_id: u64) -> Result<FuturesOrder>
+    where
+        S: Into<String>,
+    {
+        let parameters = order_id_parameters(symbol.into(), order_id);
+        let request = build_signed_request(parameters, self.recv_window)?;
+        let data = self.client.get_signed(API_V3_ORDER, &request).await?;
+        let order: FuturesOrder = from_str(data.as_str())?;
+
+        Ok(order)
+    }
+
+    /// Place a test status order
+    pub async fn test_order_status<S>(&self, symbol: S, order_id: u64) -> Result<()>
+    where
+        S: Into<String>,
+    {
+        let parameters = order_id_parameters(symbol.into(), order_id);
+        let request = build_signed_request(parameters, self.recv_window)?;
+        let data = self.client.get_signed(API_V3_ORDER_TEST, &request).await?;
+        let _: TestResponse = from_str(data.as_str())?;
+
+        Ok(())
+    }
+
+    // Place a LIMIT order - BUY
+    pub async fn limit_buy<S, F>(&self, symbol: S, qty: F, price: Num, new_client_order_id: String) -> Result<FuturesTransaction>
+    where
+        S: Into<String>,
+        F: Into<Num>,
+    {
+        let order =
+            limit_order(symbol.into(), OrderSide::Buy, qty.into(), price, new_client_order_id);
+
+        self.place_order(&order).await
+    }
+
+    /// Place a test LIMIT order - BUY
+    pub async fn test_limit_buy<S, F>(&self, symbol: S, qty: F, price: Num, new_client_order_id: String) -> Result<()>
+    where
+        S: Into<String>,
+        F: Into<Num>,
+    {
+        let order =
+            limit_order(symbol.into(), OrderSide::Buy, qty.into(), price, new_client_order_id);
+
+        self.test_order(&order).await
+    }
+
+    // Place a LIMIT order - SELL
+    pub async fn limit_sell<S, F>(&self, symbol: S, qty: F, price: Num, new_client_order_id: String) -> Result<FuturesTransaction>
+    where
+        S: Into<String>,
+        F: Into<Num>,
+    {
+        let order =
+            limit_order(symbol.into(), OrderSide::Sell, qty.into(), price, new_client_order_id);
+
+        self.place_order(&order).await
+    }
+
+    /// Place a test LIMIT order - SELL
+    pub async fn test_limit_sell<S, F>(&self, symbol: S, qty: F, price: Num, new_client_order_id: String) -> Result<()>
+    where
+        S: Into<String>,
+        F: Into<Num>,
+    {
+        let order =
+            limit_order(symbol.into(), OrderSide::Sell, qty.into(), price, new_client_order_id);
+
+        self.test_order(&order).await
+    }
+
+    // Place a MARKET order - BUY
+    pub async fn market_buy<S, F>(&self, symbol: S, qty: F, new_client_order_id: String) -> Result<FuturesTransaction>
+    where
+        S: Into<String>,
+        F: Into<Num>,
+    {
+        let order = market_order(symbol.into(), OrderSide::Buy, qty.into(), new_client_order_id);
+
+        self.place_order(&order).await
+    }
+
+    /// Place a test MARKET order - BUY
+    pub async fn test_market_buy<S, F>(&self, symbol: S, qty: F, new_client_order_id: String) -> Result<()>
+    where
+        S: Into<String>,
+        F: Into<Num>,
+    {
+        let order = market_order(symbol.into(), OrderSide::Buy, qty.into(), new_client_order_id);
+
+        self.test_order(&order).await
+    }
+
+    // Place a MARKET order - SELL
+    pub async fn market_sell<S, F>(&self, symbol: S, qty: F, new_client_order_id: String) -> Result<FuturesTransaction>
+    where
+        S: Into<String>,
+        F: Into<Num>,
+    {
+        let order = market_order(symbol.into(), OrderSide::Sell, qty.into(), new_client_order_id);
+
+        self.place_order(&order).await
+    }
+
+    /// Place a test MARKET order - SELL
+    pub async fn test_market_sell<S, F>(&self, symbol: S, qty: F, new_client_order_id: String) -> Result<()>
+    where
+        S: Into<String>,
+        F: Into<Num>,
+    {
+        let order = market_order(symbol.into(), OrderSide::Sell, qty.into(), new_client_order_id);
+
+        self.test_order(&order).await
+    }
+
+    /// Place an order of any type, see `NewOrder`
+    pub async fn place_order(&self, order: &NewOrder) -> Result<FuturesTransaction> {
+        let request = build_signed_request(order.to_parameters(), self.recv_window)?;
+        let data = self.client.post_signed(API_V3_ORDER, &request).await?;
+        let transaction: FuturesTransaction = from_str(data.as_str())?;
+
+        Ok(transaction)
+    }
+
+    /// Place an order after checking it locally against the symbol filters
+    ///
+    /// Returns `ErrorKind::InvalidOrder` without sending the order if a filter is not met.
+    pub async fn place_validated_order(&self, order: &NewOrder, rules: &SymbolRules) -> Result<FuturesTransaction> {
+        rules.validate(order)?;
+
+        self.place_order(order).await
+    }
+
+    /// Place a test order of any type
+    pub async fn test_order(&self, order: &NewOrder) -> Result<()> {
+        let request = build_signed_request(order.to_parameters(), self.recv_window)?;
+        let data = self.client.post_signed(API_V3_ORDER_TEST, &request).await?;
+        let _: TestResponse = from_str(data.as_str())?;
+
+        Ok(())
+    }
+
+    // Check an order's status
+    pub async fn cancel_order<S>(&self, symbol: S, orig_client_order_id: u64) -> Result<OrderCanceled>
+    where
+        S: Into<String>,
+    {
+        let parameters = client_order_id_parameters(symbol.into(), orig_client_order_id);
+        let request = build_signed_request(parameters, self.recv_window)?;
+        let data = self.client.delete_signed(API_V3_ORDER, &request).await?;
+        let order_canceled: OrderCanceled = from_str(data.as_str())?;
+
+        Ok(order_canceled)
+    }
+
+    /// Place a test cancel order
+    pub async fn test_cancel_order<S>(&self, symbol: S, orig_client_order_id: u64) -> Result<()>
+    where
+        S: Into<String>,
+    {
+        let parameters = client_order_id_parameters(symbol.into(), orig_client_order_id);
+        let request = build_signed_request(parameters, self.recv_window)?;
+        let data = self.client.delete_signed(API_V3_ORDER_TEST, &request).await?;
+        let _: TestResponse = from_str(data.as_str())?;
+
+        Ok(())
+    }
+
+    // Trade history
+    pub async fn trade_history<S>(&self, symbol: S) -> Result<Vec<TradeHistory>>
+    where
+        S: Into<String>,
+    {
+        let request = build_signed_request(symbol_parameters(symbol.into()), self.recv_window)?;
+        let data = self.client.get_signed("/fapi/v1/myTrades", &request).await?;
+        let trade_history: Vec<TradeHistory> = from_str(data.as_str())?;
+
+        Ok(trade_history)
+    }
+}
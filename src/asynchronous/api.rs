@@ -0,0 +1,110 @@
+use crate::asynchronous::account::*;
+use crate::asynchronous::market::*;
+use crate::asynchronous::general::*;
+use crate::asynchronous::futures::general::*;
+use crate::asynchronous::futures::market::*;
+use crate::asynchronous::userstream::*;
+use crate::asynchronous::client::*;
+use crate::config::*;
+use crate::errors::*;
+
+pub trait Binance: Sized {
+    fn new(api_key: Option<String>, secret_key: Option<String>) -> Self {
+        Self::new_with_config(api_key, secret_key, &Config::default())
+    }
+
+    fn new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Self {
+        let client = Client::new_with_config(
+            api_key,
+            secret_key,
+            config.rest_api_endpoint.clone(),
+            config,
+        );
+
+        Self::new_with_client(&client, config)
+    }
+
+    /// Like `new_with_config`, returning an error instead of panicking if the HTTP client
+    /// cannot be built.
+    fn try_new_with_config(
+        api_key: Option<String>, secret_key: Option<String>, config: &Config,
+    ) -> Result<Self> {
+        let client = Client::try_new_with_config(
+            api_key,
+            secret_key,
+            config.rest_api_endpoint.clone(),
+            config,
+        )?;
+
+        Ok(Self::new_with_client(&client, config))
+    }
+
+    /// Builds the API with RSA or Ed25519 request signing instead of HMAC.
+    fn new_with_signer<S: Signer + 'static>(
+        api_key: Option<String>, signer: S, config: &Config,
+    ) -> Self {
+        let client =
+            Client::new_with_config(api_key, None, config.rest_api_endpoint.clone(), config)
+                .with_signer(signer);
+
+        Self::new_with_client(&client, config)
+    }
+
+    /// Builds the API on top of an existing client, sharing its connection pool.
+    fn new_with_client(client: &Client, config: &Config) -> Self;
+}
+impl Binance for General {
+    fn new_with_client(client: &Client, config: &Config) -> General {
+        General {
+            client: client.with_host(config.rest_api_endpoint.as_str()),
+        }
+    }
+}
+
+impl Binance for Account {
+    fn new_with_client(client: &Client, config: &Config) -> Account {
+        Account {
+            client: client.with_host(config.rest_api_endpoint.as_str()),
+            recv_window: config.recv_window,
+        }
+    }
+}
+
+impl Binance for Market {
+    fn new_with_client(client: &Client, config: &Config) -> Market {
+        Market {
+            client: client.with_host(config.rest_api_endpoint.as_str()),
+            recv_window: config.recv_window,
+        }
+    }
+}
+
+impl Binance for UserStream {
+    fn new_with_client(client: &Client, config: &Config) -> UserStream {
+        UserStream {
+            client: client.with_host(config.futures_rest_api_endpoint.as_str()),
+            recv_window: config.recv_window,
+        }
+    }
+}
+
+// *****************************************************
+//              Binance Futures API
+// *****************************************************
+
+impl Binance for FuturesGeneral {
+    fn new_with_client(client: &Client, config: &Config) -> FuturesGeneral {
+        FuturesGeneral {
+            client: client.with_host(config.futures_rest_api_endpoint.as_str()),
+        }
+    }
+}
+
+impl Binance for FuturesMarket {
+    fn new_with_client(client: &Client, config: &Config) -> FuturesMarket {
+        FuturesMarket {
+            client: client.with_host(config.futures_rest_api_endpoint.as_str()),
+            recv_window: config.recv_window,
+        }
+    }
+}
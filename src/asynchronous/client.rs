@@ -0,0 +1,271 @@
+use crate::asynchronous::futures::general::FuturesGeneral;
+use crate::client::{
+    build_headers, build_http_client, handle_response, invalidate_time_sync, retry_after,
+    retry_delay, signed_url,
+};
+pub use crate::client::{Ed25519Signer, HmacSigner, RsaSigner, Signer};
+use crate::config::{Config, RetryPolicy};
+use crate::errors::*;
+use crate::ratelimit::RateLimiter;
+use crate::timesync::TimeSync;
+use crate::util::get_timestamp;
+use reqwest::Response;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Async counterpart of `client::Client`, backed by `reqwest::Client`.
+#[derive(Clone)]
+pub struct Client {
+    api_key: String,
+    signer: Arc<dyn Signer>,
+    host: String,
+    rate_limiter: RateLimiter,
+    retry_policy: Option<RetryPolicy>,
+    time_sync: Option<TimeSync>,
+    // Host of the server time endpoint used by `time_sync`
+    time_host: String,
+    inner_client: reqwest::Client,
+}
+
+impl Client {
+    pub fn new(api_key: Option<String>, secret_key: Option<String>, host: String) -> Self {
+        Client::new_with_config(api_key, secret_key, host, &Config::default())
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the HTTP client cannot be built, e.g. the TLS backend cannot be
+    /// initialized, like `reqwest::Client::new`. See `try_new_with_config`.
+    pub fn new_with_config(
+        api_key: Option<String>, secret_key: Option<String>, host: String, config: &Config,
+    ) -> Self {
+        Client::try_new_with_config(api_key, secret_key, host, config)
+            .expect("Failed to build the HTTP client")
+    }
+
+    /// Like `new_with_config`, returning an error if the HTTP client cannot be built.
+    pub fn try_new_with_config(
+        api_key: Option<String>, secret_key: Option<String>, host: String, config: &Config,
+    ) -> Result<Self> {
+        let inner_client = build_http_client!(reqwest::Client::builder(), config)?;
+
+        Ok(Client {
+            api_key: api_key.unwrap_or_else(|| "".into()),
+            signer: Arc::new(HmacSigner::new(&secret_key.unwrap_or_default())),
+            host,
+            rate_limiter: config.rate_limiter.clone().unwrap_or_else(|| {
+                RateLimiter::new(config.rate_limit_policy, config.rate_limit_budget)
+            }),
+            retry_policy: config.retry_policy.clone(),
+            time_sync: config.time_sync_interval.map(TimeSync::new),
+            time_host: config.futures_rest_api_endpoint.clone(),
+            inner_client,
+        })
+    }
+
+    /// Request weight and order count usage.
+    pub fn rate_limiter(&self) -> &RateLimiter {
+        &self.rate_limiter
+    }
+
+    /// Offset with the server time used to stamp signed requests, if enabled in the `Config`.
+    pub fn time_sync(&self) -> Option<&TimeSync> {
+        self.time_sync.as_ref()
+    }
+
+    /// Returns a client signing requests with `signer` instead of the HMAC secret key.
+    pub fn with_signer<S: Signer + 'static>(&self, signer: S) -> Self {
+        Client {
+            signer: Arc::new(signer),
+            ..self.clone()
+        }
+    }
+
+    /// Returns a client for another host sharing the credentials and the connection pool.
+    pub fn with_host<S: Into<String>>(&self, host: S) -> Self {
+        Client {
+            host: host.into(),
+            ..self.clone()
+        }
+    }
+
+    pub async fn get_signed(&self, endpoint: &str, request: &str) -> Result<String> {
+        self.with_retry(|| async move {
+            self.check_rate_limit(false).await?;
+            // Signed again on each attempt, with a fresh timestamp
+            let url = self.sign_request(endpoint, request).await?;
+            let response = self
+                .inner_client
+                .get(url.as_str())
+                .headers(build_headers(&self.api_key, true)?)
+                .send()
+                .await?;
+
+            self.handler(response).await
+        })
+        .await
+    }
+
+    pub async fn post_signed(&self, endpoint: &str, request: &str) -> Result<String> {
+        self.check_rate_limit(true).await?;
+        let url = self.sign_request(endpoint, request).await?;
+        let response = self
+            .inner_client
+            .post(url.as_str())
+            .headers(build_headers(&self.api_key, true)?)
+            .send()
+            .await?;
+
+        self.handler(response).await
+    }
+
+    pub async fn delete_signed(&self, endpoint: &str, request: &str) -> Result<String> {
+        self.check_rate_limit(false).await?;
+        let url = self.sign_request(endpoint, request).await?;
+        let response = self
+            .inner_client
+            .delete(url.as_str())
+            .headers(build_headers(&self.api_key, true)?)
+            .send()
+            .await?;
+
+        self.handler(response).await
+    }
+
+    pub async fn get(&self, endpoint: &str, request: &str) -> Result<String> {
+        let mut url: String = format!("{}{}", self.host, endpoint);
+        if !request.is_empty() {
+            url.push_str(format!("?{}", request).as_str());
+        }
+        let url = &url;
+
+        self.with_retry(|| async move {
+            self.check_rate_limit(false).await?;
+            let response = self.inner_client.get(url.as_str()).send().await?;
+
+            self.handler(response).await
+        })
+        .await
+    }
+
+    pub async fn post(&self, endpoint: &str) -> Result<String> {
+        self.check_rate_limit(false).await?;
+        let url: String = format!("{}{}", self.host, endpoint);
+
+        let response = self
+            .inner_client
+            .post(url.as_str())
+            .headers(build_headers(&self.api_key, false)?)
+            .send()
+            .await?;
+
+        self.handler(response).await
+    }
+
+    pub async fn put(&self, endpoint: &str, listen_key: &str) -> Result<String> {
+        self.check_rate_limit(false).await?;
+        let url: String = format!("{}{}", self.host, endpoint);
+        let data: String = format!("listenKey={}", listen_key);
+
+        let response = self
+            .inner_client
+            .put(url.as_str())
+            .headers(build_headers(&self.api_key, false)?)
+            .body(data)
+            .send()
+            .await?;
+
+        self.handler(response).await
+    }
+
+    pub async fn delete(&self, endpoint: &str, listen_key: &str) -> Result<String> {
+        self.check_rate_limit(false).await?;
+        let url: String = format!("{}{}", self.host, endpoint);
+        let data: String = format!("listenKey={}", listen_key);
+
+        let response = self
+            .inner_client
+            .delete(url.as_str())
+            .headers(build_headers(&self.api_key, false)?)
+            .body(data)
+            .send()
+            .await?;
+
+        self.handler(response).await
+    }
+
+    async fn sign_request(&self, endpoint: &str, request: &str) -> Result<String> {
+        let timestamp = self.timestamp().await?;
+
+        signed_url(&self.host, self.signer.as_ref(), endpoint, request, timestamp)
+    }
+
+    async fn timestamp(&self) -> Result<u64> {
+        let time_sync = match self.time_sync {
+            Some(ref time_sync) => time_sync,
+            None => return get_timestamp(),
+        };
+
+        if let Some(_syncing) = time_sync.try_begin_sync() {
+            let general = FuturesGeneral {
+                client: Client {
+                    host: self.time_host.clone(),
+                    time_sync: None,
+                    ..self.clone()
+                },
+            };
+            // A failed resync keeps the previous offset
+            if let Err(e) = time_sync.sync_async(&general).await {
+                if !time_sync.is_synced() {
+                    return Err(e);
+                }
+            }
+        }
+
+        time_sync.timestamp()
+    }
+
+    async fn check_rate_limit(&self, orders: bool) -> Result<()> {
+        if let Some(wait) = self.rate_limiter.check(orders)? {
+            tokio::time::delay_for(wait).await;
+        }
+
+        Ok(())
+    }
+
+    // Only used for idempotent requests
+    async fn with_retry<F, Fut>(&self, request: F) -> Result<String>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<String>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match request().await {
+                Err(e) => match self.retry_policy.as_ref().and_then(|p| retry_delay(p, attempt, &e)) {
+                    Some(delay) => {
+                        tokio::time::delay_for(delay).await;
+                        attempt += 1;
+                    }
+                    None => return Err(e),
+                },
+                result => return result,
+            }
+        }
+    }
+
+    async fn handler(&self, response: Response) -> Result<String> {
+        self.rate_limiter.update(response.headers());
+        let retry_after = retry_after(response.headers());
+        let status = response.status();
+        let body = response.text().await?;
+
+        let result = handle_response(status, retry_after, body);
+        if let Err(ref e) = result {
+            invalidate_time_sync(self.time_sync.as_ref(), e);
+        }
+
+        result
+    }
+}
@@ -0,0 +1,193 @@
+//! Async version of `futures::market`.
+
+use crate::util::*;
+use crate::futures::model::*;
+use crate::asynchronous::client::*;
+use crate::errors::*;
+use serde_json::{Value, from_str};
+
+#[derive(Clone)]
+pub struct FuturesMarket {
+    pub client: Client,
+    pub recv_window: u64,
+}
+
+impl FuturesMarket {
+    // Order book (Default 100; max 1000)
+    pub async fn get_depth<S>(&self, symbol: S) -> Result<OrderBook>
+    where
+        S: Into<String>,
+    {
+        let request = build_symbol_request(symbol.into());
+
+        let data = self.client.get("/fapi/v1/depth", &request).await?;
+
+        let order_book: OrderBook = from_str(data.as_str())?;
+
+        Ok(order_book)
+    }
+
+    pub async fn get_trades<S>(&self, symbol: S) -> Result<Trades>
+    where
+        S: Into<String>,
+    {
+        let request = build_symbol_request(symbol.into());
+
+        let data = self.client.get("/fapi/v1/trades", &request).await?;
+
+        let trades: Trades = from_str(data.as_str())?;
+
+        Ok(trades)
+    }
+
+    // TODO This may be incomplete, as it hasn't been tested
+    pub async fn get_historical_trades<S1, S2, S3>(
+        &self, symbol: S1, from_id: S2, limit: S3,
+    ) -> Result<Trades>
+    where
+        S1: Into<String>,
+        S2: Into<Option<u64>>,
+        S3: Into<Option<u16>>,
+    {
+        let parameters = historical_trades_parameters(symbol.into(), from_id.into(), limit.into());
+        let request = build_signed_request(parameters, self.recv_window)?;
+
+        let data = self
+            .client
+            .get_signed("/fapi/v1/historicalTrades", &request)
+            .await?;
+
+        let trades: Trades = from_str(data.as_str())?;
+
+        Ok(trades)
+    }
+
+    pub async fn get_agg_trades<S1, S2, S3, S4, S5>(
+        &self, symbol: S1, from_id: S2, start_time: S3, end_time: S4, limit: S5,
+    ) -> Result<AggTrades>
+    where
+        S1: Into<String>,
+        S2: Into<Option<u64>>,
+        S3: Into<Option<u64>>,
+        S4: Into<Option<u64>>,
+        S5: Into<Option<u16>>,
+    {
+        let request = build_agg_trades_request(
+            symbol.into(),
+            from_id.into(),
+            start_time.into(),
+            end_time.into(),
+            limit.into(),
+        );
+
+        let data = self.client.get("/fapi/v1/aggTrades", &request).await?;
+
+        let aggtrades: AggTrades = from_str(data.as_str())?;
+
+        Ok(aggtrades)
+    }
+
+    // Returns up to 'limit' klines for given symbol and interval (KlineInterval::Minutes1, "5m", ...)
+    // https://github.com/binance-exchange/binance-official-api-docs/blob/master/rest-api.md#klinecandlestick-data
+    pub async fn get_klines<S1, S2, S3, S4, S5>(
+        &self, symbol: S1, interval: S2, limit: S3, start_time: S4, end_time: S5,
+    ) -> Result<KlineSummaries>
+    where
+        S1: Into<String>,
+        S2: Into<KlineInterval>,
+        S3: Into<Option<u16>>,
+        S4: Into<Option<u64>>,
+        S5: Into<Option<u64>>,
+    {
+        let request = build_klines_request(
+            symbol.into(),
+            interval.into(),
+            limit.into(),
+            start_time.into(),
+            end_time.into(),
+        );
+
+        let data = self.client.get("/fapi/v1/klines", &request).await?;
+        let parsed_data: Vec<Vec<Value>> = from_str(data.as_str())?;
+
+        let klines = to_kline_summaries(&parsed_data);
+        Ok(klines)
+    }
+
+    // 24hr ticker price change statistics
+    pub async fn get_24h_price_stats<S>(&self, symbol: S) -> Result<PriceStats>
+    where
+        S: Into<String>,
+    {
+        let request = build_symbol_request(symbol.into());
+
+        let data = self.client.get("/fapi/v1/ticker/24hr", &request).await?;
+
+        let stats: PriceStats = from_str(data.as_str())?;
+
+        Ok(stats)
+    }
+
+    // Latest price for ONE symbol.
+    pub async fn get_price<S>(&self, symbol: S) -> Result<SymbolPrice>
+    where
+        S: Into<String>,
+    {
+        let request = build_symbol_request(symbol.into());
+
+        let data = self.client.get("/fapi/v1/ticker/price", &request).await?;
+        let symbol_price: SymbolPrice = from_str(data.as_str())?;
+
+        Ok(symbol_price)
+    }
+
+    // Symbols order book ticker
+    // -> Best price/qty on the order book for ALL symbols.
+    pub async fn get_all_book_tickers(&self) -> Result<BookTickers> {
+        let data = self.client.get("/fapi/v1/ticker/bookTicker", "").await?;
+
+        let book_tickers: BookTickers = from_str(data.as_str())?;
+
+        Ok(book_tickers)
+    }
+
+    // -> Best price/qty on the order book for ONE symbol
+    pub async fn get_book_ticker<S>(&self, symbol: S) -> Result<Tickers>
+    where
+        S: Into<String>,
+    {
+        let request = build_symbol_request(symbol.into());
+
+        let data = self.client.get("/fapi/v1/ticker/bookTicker", &request).await?;
+        let ticker: Tickers = from_str(data.as_str())?;
+
+        Ok(ticker)
+    }
+
+    pub async fn get_mark_prices(&self) -> Result<MarkPrices> {
+        let data = self.client.get("/fapi/v1/premiumIndex", "").await?;
+
+        let mark_prices: MarkPrices = from_str(data.as_str())?;
+
+        Ok(mark_prices)
+    }
+
+    pub async fn get_all_liquidation_orders(&self) -> Result<LiquidationOrders> {
+        let data = self.client.get("/fapi/v1/allForceOrders", "").await?;
+        let liquidation_orders: LiquidationOrders = from_str(data.as_str())?;
+
+        Ok(liquidation_orders)
+    }
+
+    pub async fn open_interest<S>(&self, symbol: S) -> Result<OpenInterest>
+    where
+        S: Into<String>,
+    {
+        let request = build_symbol_request(symbol.into());
+
+        let data = self.client.get("/fapi/v1/openInterest", &request).await?;
+        let open_interest: OpenInterest = from_str(data.as_str())?;
+
+        Ok(open_interest)
+    }
+}
@@ -0,0 +1,68 @@
+use crate::model::*;
+use crate::asynchronous::client::*;
+use crate::errors::*;
+use crate::registry::SpotSymbolRegistry;
+
+use serde_json::from_str;
+
+#[derive(Clone)]
+pub struct General {
+    pub client: Client,
+}
+
+impl General {
+    // Test connectivity
+    pub async fn ping(&self) -> Result<String> {
+        self.client.get("/api/v3/ping", "").await?;
+
+        Ok("pong".into())
+    }
+
+    // Check server time
+    pub async fn get_server_time(&self) -> Result<ServerTime> {
+        let data: String = self.client.get("/api/v3/time", "").await?;
+
+        let server_time: ServerTime = from_str(data.as_str())?;
+
+        Ok(server_time)
+    }
+
+    // Obtain exchange information
+    // - Current exchange trading rules and symbol information
+    pub async fn exchange_info(&self) -> Result<ExchangeInformation> {
+        let data: String = self.client.get("/api/v3/exchangeInfo", "").await?;
+
+        let info: ExchangeInformation = from_str(data.as_str())?;
+        self.client.rate_limiter().set_limits(&info.rate_limits);
+
+        Ok(info)
+    }
+
+    // Fill a symbol registry with the current exchange information
+    // - Async clients can't refresh the registry on lookup, call this when `is_expired()`
+    pub async fn refresh_symbol_registry(&self, registry: &SpotSymbolRegistry) -> Result<()> {
+        let info = self.exchange_info().await?;
+        registry.load(info);
+
+        Ok(())
+    }
+
+    // Get Symbol information
+    pub async fn get_symbol_info<S>(&self, symbol: S) -> Result<Symbol>
+    where
+        S: Into<String>,
+    {
+        let upper_symbol = symbol.into().to_uppercase();
+        match self.exchange_info().await {
+            Ok(info) => {
+                for item in info.symbols {
+                    if item.symbol == upper_symbol {
+                        return Ok(item);
+                    }
+                }
+                bail!("Symbol not found")
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
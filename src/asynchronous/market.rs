@@ -0,0 +1,127 @@
+use crate::util::*;
+use crate::model::*;
+use crate::asynchronous::client::*;
+use crate::errors::*;
+use serde_json::{Value, from_str};
+
+#[derive(Clone)]
+pub struct Market {
+    pub client: Client,
+    pub recv_window: u64,
+}
+
+// Market Data endpoints
+impl Market {
+    // Order book (Default 100; max 100)
+    pub async fn get_depth<S>(&self, symbol: S) -> Result<OrderBook>
+    where
+        S: Into<String>,
+    {
+        let request = build_symbol_request(symbol.into());
+
+        let data = self.client.get("/api/v3/depth", &request).await?;
+
+        let order_book: OrderBook = from_str(data.as_str())?;
+
+        Ok(order_book)
+    }
+
+    // Latest price for ALL symbols.
+    pub async fn get_all_prices(&self) -> Result<Prices> {
+        let data = self.client.get("/api/v3/ticker/price", "").await?;
+
+        let prices: Prices = from_str(data.as_str())?;
+
+        Ok(prices)
+    }
+
+    // Latest price for ONE symbol.
+    pub async fn get_price<S>(&self, symbol: S) -> Result<SymbolPrice>
+    where
+        S: Into<String>,
+    {
+        let request = build_symbol_request(symbol.into());
+
+        let data = self.client.get("/api/v3/ticker/price", &request).await?;
+        let symbol_price: SymbolPrice = from_str(data.as_str())?;
+
+        Ok(symbol_price)
+    }
+
+    // Average price for ONE symbol.
+    pub async fn get_average_price<S>(&self, symbol: S) -> Result<AveragePrice>
+    where
+        S: Into<String>,
+    {
+        let request = build_symbol_request(symbol.into());
+
+        let data = self.client.get("/api/v3/avgPrice", &request).await?;
+        let average_price: AveragePrice = from_str(data.as_str())?;
+
+        Ok(average_price)
+    }
+
+    // Symbols order book ticker
+    // -> Best price/qty on the order book for ALL symbols.
+    pub async fn get_all_book_tickers(&self) -> Result<BookTickers> {
+        let data = self.client.get("/api/v3/ticker/bookTicker", "").await?;
+
+        let book_tickers: BookTickers = from_str(data.as_str())?;
+
+        Ok(book_tickers)
+    }
+
+    // -> Best price/qty on the order book for ONE symbol
+    pub async fn get_book_ticker<S>(&self, symbol: S) -> Result<Tickers>
+    where
+        S: Into<String>,
+    {
+        let request = build_symbol_request(symbol.into());
+
+        let data = self.client.get("/api/v3/ticker/bookTicker", &request).await?;
+        let ticker: Tickers = from_str(data.as_str())?;
+
+        Ok(ticker)
+    }
+
+    // 24hr ticker price change statistics
+    pub async fn get_24h_price_stats<S>(&self, symbol: S) -> Result<PriceStats>
+    where
+        S: Into<String>,
+    {
+        let request = build_symbol_request(symbol.into());
+
+        let data = self.client.get("/api/v3/ticker/24hr", &request).await?;
+
+        let stats: PriceStats = from_str(data.as_str())?;
+
+        Ok(stats)
+    }
+
+    // Returns up to 'limit' klines for given symbol and interval (KlineInterval::Minutes1, "5m", ...)
+    // https://github.com/binance-exchange/binance-official-api-docs/blob/master/rest-api.md#klinecandlestick-data
+    pub async fn get_klines<S1, S2, S3, S4, S5>(
+        &self, symbol: S1, interval: S2, limit: S3, start_time: S4, end_time: S5,
+    ) -> Result<KlineSummaries>
+    where
+        S1: Into<String>,
+        S2: Into<KlineInterval>,
+        S3: Into<Option<u16>>,
+        S4: Into<Option<u64>>,
+        S5: Into<Option<u64>>,
+    {
+        let request = build_klines_request(
+            symbol.into(),
+            interval.into(),
+            limit.into(),
+            start_time.into(),
+            end_time.into(),
+        );
+
+        let data = self.client.get("/api/v3/klines", &request).await?;
+        let parsed_data: Vec<Vec<Value>> = from_str(data.as_str())?;
+
+        let klines = to_kline_summaries(&parsed_data);
+        Ok(klines)
+    }
+}
@@ -0,0 +1,17 @@
+//! Async (tokio) versions of the REST APIs and of the WebSocket client,
+//! enabled with the `async` feature.
+//!
+//! The structs mirror their blocking counterparts and share the same models,
+//! so `asynchronous::account::Account` returns the same `FuturesTransaction`
+//! as `account::Account`.
+
+pub mod api;
+pub mod client;
+
+pub mod account;
+pub mod general;
+pub mod market;
+pub mod userstream;
+pub mod websockets;
+
+pub mod futures;
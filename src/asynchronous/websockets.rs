@@ -0,0 +1,271 @@
+use crate::config::*;
+use crate::errors::*;
+use crate::streams::StreamName;
+use crate::websockets::{
+    combined_stream_url, Subscriptions, Watchdog, WebsocketEvent,
+    LIST_SUBSCRIPTIONS, SUBSCRIBE, UNSUBSCRIBE,
+};
+
+use futures::stream::Stream;
+use futures::task::{Context, Poll};
+use futures::{FutureExt, SinkExt, StreamExt};
+use std::pin::Pin;
+use tokio::net::TcpStream;
+use tokio::time::{self, Delay};
+use tokio_tls::{TlsConnector, TlsStream};
+use tokio_tungstenite::stream::Stream as StreamSwitcher;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{client_async, WebSocketStream};
+use url::Url;
+
+type AutoStream = StreamSwitcher<TcpStream, TlsStream<TcpStream>>;
+
+/// Async WebSocket client yielding `WebsocketEvent`s as a `futures::Stream`.
+///
+/// Messages which can't be decoded are yielded as `WebsocketEvent::Unknown` or
+/// `WebsocketEvent::Invalid`, as by `WebSockets`, without ending the stream.
+///
+/// The stream ends when the server closes the connection. With `Config::ws_heartbeat`
+/// it yields `ErrorKind::StaleConnection` once when the connection went silent, then ends.
+pub struct WebSockets {
+    socket: WebSocketStream<AutoStream>,
+    subscriptions: Subscriptions,
+    watchdog: Watchdog,
+    // Wakes the stream up for the next heartbeat check
+    timer: Option<Delay>,
+    // Set once the heartbeat failed, the stream then ends
+    terminated: bool,
+}
+
+impl WebSockets {
+    pub async fn connect(endpoint: &str) -> Result<WebSockets> {
+        Self::connect_with_config(endpoint, &Config::default()).await
+    }
+
+    pub async fn connect_with_config(endpoint: &str, config: &Config) -> Result<WebSockets> {
+        let url = combined_stream_url(config, endpoint)?;
+        let stream = connect_stream(&url).await?;
+
+        match client_async(url, stream).await {
+            Ok((socket, _)) => Ok(WebSockets {
+                socket,
+                subscriptions: Subscriptions::new(endpoint),
+                watchdog: Watchdog::new(config.ws_heartbeat.clone()),
+                timer: None,
+                terminated: false,
+            }),
+            Err(e) => {
+                bail!(format!("Error during handshake {}", e));
+            }
+        }
+    }
+
+    pub async fn connect_streams(streams: &[StreamName], config: &Config) -> Result<WebSockets> {
+        Self::connect_with_config(&StreamName::combined_endpoint(streams), config).await
+    }
+
+    /// Adds streams to the open connection, without reconnecting.
+    ///
+    /// Returns the id of the request. The answer is yielded by the stream, as
+    /// `WebsocketEvent::Subscribed` or `WebsocketEvent::SubscriptionError` with the same id.
+    pub async fn subscribe(&mut self, streams: &[&str]) -> Result<u64> {
+        self.send_request(SUBSCRIBE, streams).await
+    }
+
+    /// Removes streams from the open connection, answered with `WebsocketEvent::Unsubscribed`.
+    pub async fn unsubscribe(&mut self, streams: &[&str]) -> Result<u64> {
+        self.send_request(UNSUBSCRIBE, streams).await
+    }
+
+    /// Asks for the streams of the connection, answered with `WebsocketEvent::Subscriptions`.
+    pub async fn list_subscriptions(&mut self) -> Result<u64> {
+        self.send_request(LIST_SUBSCRIPTIONS, &[]).await
+    }
+
+    /// Streams of the connection, as of the last answered request.
+    pub fn streams(&self) -> &[String] {
+        self.subscriptions.streams()
+    }
+
+    async fn send_request(&mut self, method: &'static str, streams: &[&str]) -> Result<u64> {
+        let (id, request) = self.subscriptions.request(method, streams);
+        self.socket.send(Message::Text(request)).await?;
+
+        Ok(id)
+    }
+
+    pub async fn disconnect(&mut self) -> Result<()> {
+        self.socket.close(None).await?;
+
+        Ok(())
+    }
+
+    // Pings and checks for a stale connection while no message arrives. Ready when
+    // the timer of the next check already elapsed.
+    fn poll_heartbeat(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        if self.watchdog.check()? {
+            if let Poll::Ready(Ok(())) = self.socket.poll_ready_unpin(cx) {
+                self.socket.start_send_unpin(Message::Ping(Vec::new()))?;
+            }
+        }
+        if let Poll::Ready(Err(e)) = self.socket.poll_flush_unpin(cx) {
+            return Poll::Ready(Err(e.into()));
+        }
+
+        let next_check = match self.watchdog.next_check() {
+            Some(next_check) => time::Instant::from_std(next_check),
+            None => return Poll::Pending,
+        };
+        let timer = self.timer.get_or_insert_with(|| time::delay_until(next_check));
+        timer.reset(next_check);
+
+        timer.poll_unpin(cx).map(Ok)
+    }
+}
+
+async fn connect_stream(url: &Url) -> Result<AutoStream> {
+    let host = match url.host_str() {
+        Some(host) => host,
+        None => bail!(format!("Missing host in {}", url)),
+    };
+    let port = match url.port_or_known_default() {
+        Some(port) => port,
+        None => bail!(format!("Missing port in {}", url)),
+    };
+    let socket = TcpStream::connect((host, port)).await?;
+
+    if url.scheme() != "wss" {
+        return Ok(StreamSwitcher::Plain(socket));
+    }
+
+    let connector: TlsConnector = match native_tls::TlsConnector::new() {
+        Ok(connector) => connector.into(),
+        Err(e) => bail!(format!("Error creating the TLS connector {}", e)),
+    };
+    match connector.connect(host, socket).await {
+        Ok(tls_stream) => Ok(StreamSwitcher::Tls(tls_stream)),
+        Err(e) => bail!(format!("Error during TLS handshake {}", e)),
+    }
+}
+
+impl Stream for WebSockets {
+    type Item = Result<WebsocketEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.terminated {
+            return Poll::Ready(None);
+        }
+
+        loop {
+            let message = match self.socket.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(message))) => message,
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e.into()))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => match self.poll_heartbeat(cx) {
+                    Poll::Ready(Ok(())) => continue,
+                    Poll::Ready(Err(e)) => {
+                        self.terminated = true;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                    Poll::Pending => return Poll::Pending,
+                },
+            };
+            self.watchdog.received();
+
+            match message {
+                Message::Text(msg) => {
+                    if let Some(event) = self.subscriptions.decode(msg) {
+                        return Poll::Ready(Some(Ok(event)));
+                    }
+                }
+                Message::Ping(_) | Message::Pong(_) | Message::Binary(_) => {}
+                Message::Close(_) => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn stale_connection_ends_the_stream() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (done, wait_done) = mpsc::channel::<()>();
+
+        // Accepts, then never reads: the pings are left unanswered
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let _socket = tungstenite::accept(stream).unwrap();
+            let _ = wait_done.recv();
+        });
+
+        let config = Config::default()
+            .set_ws_endpoint(format!("ws://127.0.0.1:{}", port))
+            .set_ws_heartbeat(Heartbeat {
+                ping_interval: Duration::from_millis(10),
+                stale_timeout: Duration::from_millis(50),
+            });
+        let mut web_socket = WebSockets::connect_with_config("bnbbtc@aggTrade", &config)
+            .await
+            .unwrap();
+
+        match web_socket.next().await {
+            Some(Err(Error(ErrorKind::StaleConnection(silence), _))) => {
+                assert!(silence >= Duration::from_millis(50))
+            }
+            _ => panic!("expected StaleConnection"),
+        }
+        assert!(web_socket.next().await.is_none());
+        assert!(web_socket.next().await.is_none());
+
+        done.send(()).unwrap();
+        server.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn undecodable_messages_yielded_as_events() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut socket = tungstenite::accept(stream).unwrap();
+            let invalid = r#"{"stream": "bnbbtc@aggTrade", "data": {"e": "aggTrade"}}"#;
+            for message in &["not json", invalid] {
+                socket
+                    .write_message(Message::Text(message.to_string()))
+                    .unwrap();
+            }
+            socket.close(None).unwrap();
+            while socket.read_message().is_ok() {}
+        });
+
+        let config = Config::default().set_ws_endpoint(format!("ws://127.0.0.1:{}", port));
+        let mut web_socket = WebSockets::connect_with_config("bnbbtc@aggTrade", &config)
+            .await
+            .unwrap();
+
+        match web_socket.next().await {
+            Some(Ok(WebsocketEvent::Unknown(message))) => assert_eq!(message, "not json"),
+            _ => panic!("expected Unknown"),
+        }
+        match web_socket.next().await {
+            Some(Ok(WebsocketEvent::Invalid {
+                error: Error(ErrorKind::InvalidEvent(event_type, _), _),
+                ..
+            })) => assert_eq!(event_type, "aggTrade"),
+            _ => panic!("expected Invalid"),
+        }
+        assert!(web_socket.next().await.is_none());
+
+        drop(web_socket);
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,252 @@
+use crate::ratelimit::{RateLimitPolicy, RateLimiter};
+use std::time::Duration;
+
+static REST_API_ENDPOINT: &str = "https://fapi.binance.com";
+static FUTURES_REST_API_ENDPOINT: &str = "https://fapi.binance.com";
+static WS_ENDPOINT: &str = "wss://fstream.binance.com";
+static WS_API_ENDPOINT: &str = "wss://ws-fapi.binance.com/ws-fapi/v1";
+
+static USER_AGENT: &str = "binance-rs";
+
+static TESTNET_FUTURES_REST_API_ENDPOINT: &str = "https://testnet.binancefuture.com";
+static TESTNET_WS_ENDPOINT: &str = "wss://stream.binancefuture.com";
+static TESTNET_WS_API_ENDPOINT: &str = "wss://testnet.binancefuture.com/ws-fapi/v1";
+
+/// Connection settings shared by the REST and WebSocket clients.
+///
+/// `Config::default()` targets the production futures endpoints, `Config::testnet()`
+/// the futures testnet. Any endpoint can be overridden, e.g. to point the clients
+/// at a local mock server.
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub rest_api_endpoint: String,
+    pub futures_rest_api_endpoint: String,
+    pub ws_endpoint: String,
+    /// Endpoint of the WebSocket API, see `WsApiClient`.
+    pub ws_api_endpoint: String,
+
+    pub recv_window: u64,
+
+    // HTTP client settings, applied once when the connection pool is built.
+    /// Timeout (in seconds) for a whole REST request. `None` keeps reqwest's default.
+    pub timeout: Option<u64>,
+    /// Timeout (in seconds) for establishing a connection.
+    pub connect_timeout: Option<u64>,
+    pub proxy: Option<reqwest::Proxy>,
+    /// Maximum number of idle keep-alive connections kept per host.
+    pub pool_max_idle_per_host: Option<usize>,
+    /// Time (in seconds) an idle connection is kept in the pool.
+    pub pool_idle_timeout: Option<u64>,
+    pub user_agent: String,
+
+    pub rate_limit_policy: RateLimitPolicy,
+    /// Fraction (0.0 - 1.0) of the exchange rate limits the client may use
+    /// before `rate_limit_policy` applies.
+    pub rate_limit_budget: f64,
+    /// Limiter shared by every client built with this config, instead of one per client
+    /// built from `rate_limit_policy`, see `set_rate_limiter`.
+    pub rate_limiter: Option<RateLimiter>,
+
+    /// Retry of idempotent GET requests, disabled when `None`.
+    pub retry_policy: Option<RetryPolicy>,
+
+    /// How often signed requests resync the offset with the server time,
+    /// disabled (local clock only) when `None`.
+    pub time_sync_interval: Option<Duration>,
+
+    /// Backoff between reconnection attempts of `WebSockets::event_loop` after the
+    /// connection was lost, which then fails instead when `None`.
+    pub ws_reconnect: Option<RetryPolicy>,
+
+    /// Pings and stale connection watchdog of the WebSocket connections, disabled
+    /// (the server pings are still answered) when `None`.
+    pub ws_heartbeat: Option<Heartbeat>,
+}
+
+/// Exponential backoff for retrying idempotent GET requests which failed with
+/// HTTP 429 (too many requests), a timeout or a connection error.
+///
+/// Requests answered with HTTP 418 (IP banned) are never retried.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            multiplier: 2.0,
+        }
+    }
+}
+
+/// Heartbeat of a WebSocket connection: the client pings the server every `ping_interval`,
+/// and the connection is considered stale once nothing, not even a pong, was received
+/// for `stale_timeout`.
+///
+/// A stale connection fails with `ErrorKind::StaleConnection`, or is reconnected when
+/// `Config::ws_reconnect` is set.
+#[derive(Clone, Debug)]
+pub struct Heartbeat {
+    pub ping_interval: Duration,
+    pub stale_timeout: Duration,
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Heartbeat {
+            ping_interval: Duration::from_secs(30),
+            stale_timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before the retry number `attempt` (starting at 0), capped at `max_backoff`.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let backoff = self.initial_backoff.as_secs_f64() * self.multiplier.powi(attempt as i32);
+        if backoff.is_finite() && backoff < self.max_backoff.as_secs_f64() {
+            Duration::from_secs_f64(backoff.max(0.0))
+        } else {
+            self.max_backoff
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            rest_api_endpoint: REST_API_ENDPOINT.into(),
+            futures_rest_api_endpoint: FUTURES_REST_API_ENDPOINT.into(),
+            ws_endpoint: WS_ENDPOINT.into(),
+            ws_api_endpoint: WS_API_ENDPOINT.into(),
+
+            recv_window: 5000,
+
+            timeout: None,
+            connect_timeout: None,
+            proxy: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: None,
+            user_agent: USER_AGENT.into(),
+
+            rate_limit_policy: RateLimitPolicy::Track,
+            rate_limit_budget: 1.0,
+            rate_limiter: None,
+
+            retry_policy: None,
+
+            time_sync_interval: None,
+
+            ws_reconnect: None,
+
+            ws_heartbeat: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn testnet() -> Self {
+        Config::default()
+            .set_rest_api_endpoint(TESTNET_FUTURES_REST_API_ENDPOINT)
+            .set_futures_rest_api_endpoint(TESTNET_FUTURES_REST_API_ENDPOINT)
+            .set_ws_endpoint(TESTNET_WS_ENDPOINT)
+            .set_ws_api_endpoint(TESTNET_WS_API_ENDPOINT)
+    }
+
+    pub fn set_rest_api_endpoint<T: Into<String>>(mut self, rest_api_endpoint: T) -> Self {
+        self.rest_api_endpoint = rest_api_endpoint.into();
+        self
+    }
+
+    pub fn set_futures_rest_api_endpoint<T: Into<String>>(
+        mut self, futures_rest_api_endpoint: T,
+    ) -> Self {
+        self.futures_rest_api_endpoint = futures_rest_api_endpoint.into();
+        self
+    }
+
+    pub fn set_ws_endpoint<T: Into<String>>(mut self, ws_endpoint: T) -> Self {
+        self.ws_endpoint = ws_endpoint.into();
+        self
+    }
+
+    pub fn set_ws_api_endpoint<T: Into<String>>(mut self, ws_api_endpoint: T) -> Self {
+        self.ws_api_endpoint = ws_api_endpoint.into();
+        self
+    }
+
+    pub fn set_recv_window(mut self, recv_window: u64) -> Self {
+        self.recv_window = recv_window;
+        self
+    }
+
+    pub fn set_timeout(mut self, timeout: Option<u64>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn set_connect_timeout(mut self, connect_timeout: Option<u64>) -> Self {
+        self.connect_timeout = connect_timeout;
+        self
+    }
+
+    pub fn set_proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    pub fn set_pool_max_idle_per_host(mut self, pool_max_idle_per_host: usize) -> Self {
+        self.pool_max_idle_per_host = Some(pool_max_idle_per_host);
+        self
+    }
+
+    pub fn set_pool_idle_timeout(mut self, pool_idle_timeout: Option<u64>) -> Self {
+        self.pool_idle_timeout = pool_idle_timeout;
+        self
+    }
+
+    pub fn set_user_agent<T: Into<String>>(mut self, user_agent: T) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    pub fn set_rate_limit_policy(mut self, rate_limit_policy: RateLimitPolicy, budget: f64) -> Self {
+        self.rate_limit_policy = rate_limit_policy;
+        self.rate_limit_budget = budget;
+        self
+    }
+
+    /// Shares `rate_limiter` between the clients built with this config, so that the
+    /// limits loaded by `exchange_info()` and the usage apply to all of them.
+    pub fn set_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    pub fn set_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    pub fn set_time_sync(mut self, resync_interval: Duration) -> Self {
+        self.time_sync_interval = Some(resync_interval);
+        self
+    }
+
+    pub fn set_ws_reconnect(mut self, reconnect_policy: RetryPolicy) -> Self {
+        self.ws_reconnect = Some(reconnect_policy);
+        self
+    }
+
+    pub fn set_ws_heartbeat(mut self, heartbeat: Heartbeat) -> Self {
+        self.ws_heartbeat = Some(heartbeat);
+        self
+    }
+}
@@ -1,18 +1,221 @@
+use crate::ratelimit::RateLimitUsage;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::time::Duration;
 
 #[derive(Debug, Deserialize)]
 pub struct BinanceContentError {
     pub code: i16,
     pub msg: String,
 
+    #[allow(dead_code)]
     #[serde(flatten)]
     extra: HashMap<String, Value>,
 }
 
+impl BinanceContentError {
+    pub fn error_code(&self) -> BinanceErrorCode {
+        BinanceErrorCode::from(self.code)
+    }
+}
+
+macro_rules! binance_error_codes {
+    ($($(#[$doc:meta])* $name:ident = $code:expr,)*) => {
+        /// Documented error codes of the futures and spot APIs.
+        ///
+        /// Codes missing from the list are kept as `Other(code)`.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum BinanceErrorCode {
+            $($(#[$doc])* $name,)*
+            Other(i16),
+        }
+
+        impl From<i16> for BinanceErrorCode {
+            fn from(code: i16) -> Self {
+                match code {
+                    $($code => BinanceErrorCode::$name,)*
+                    code => BinanceErrorCode::Other(code),
+                }
+            }
+        }
+
+        impl BinanceErrorCode {
+            pub fn code(self) -> i16 {
+                match self {
+                    $(BinanceErrorCode::$name => $code,)*
+                    BinanceErrorCode::Other(code) => code,
+                }
+            }
+        }
+    };
+}
+
+binance_error_codes! {
+    // 10xx - General server or network issues
+    Unknown = -1000,
+    Disconnected = -1001,
+    Unauthorized = -1002,
+    TooManyRequests = -1003,
+    DuplicateIp = -1004,
+    NoSuchIp = -1005,
+    UnexpectedResponse = -1006,
+    Timeout = -1007,
+    ServerBusy = -1008,
+    ErrorMsgReceived = -1010,
+    NonWhiteList = -1011,
+    InvalidMessage = -1013,
+    UnknownOrderComposition = -1014,
+    TooManyOrders = -1015,
+    ServiceShuttingDown = -1016,
+    UnsupportedOperation = -1020,
+    /// Timestamp outside of the recvWindow, or ahead of the server time
+    InvalidTimestamp = -1021,
+    InvalidSignature = -1022,
+    StartTimeGreaterThanEndTime = -1023,
+
+    // 11xx - Request issues
+    IllegalChars = -1100,
+    TooManyParameters = -1101,
+    MandatoryParamEmptyOrMalformed = -1102,
+    UnknownParam = -1103,
+    UnreadParameters = -1104,
+    ParamEmpty = -1105,
+    ParamNotRequired = -1106,
+    BadPrecision = -1111,
+    NoDepth = -1112,
+    TifNotRequired = -1114,
+    InvalidTif = -1115,
+    InvalidOrderType = -1116,
+    InvalidSide = -1117,
+    EmptyNewClOrdId = -1118,
+    EmptyOrgClOrdId = -1119,
+    BadInterval = -1120,
+    BadSymbol = -1121,
+    InvalidListenKey = -1125,
+    MoreThanXxHours = -1127,
+    OptionalParamsBadCombo = -1128,
+    InvalidParameter = -1130,
+    InvalidNewOrderRespType = -1136,
+
+    // 20xx - Processing issues
+    NewOrderRejected = -2010,
+    /// Unknown order sent on cancel
+    CancelRejected = -2011,
+    NoSuchOrder = -2013,
+    BadApiKeyFmt = -2014,
+    RejectedMbxKey = -2015,
+    BalanceNotSufficient = -2018,
+    MarginNotSufficient = -2019,
+    UnableToFill = -2020,
+    OrderWouldImmediatelyTrigger = -2021,
+    ReduceOnlyReject = -2022,
+    PositionNotSufficient = -2024,
+    MaxOpenOrderExceeded = -2025,
+    MaxLeverageRatio = -2027,
+    MinLeverageRatio = -2028,
+
+    // 40xx - Filters and other issues
+    InvalidOrderStatus = -4000,
+    PriceLessThanZero = -4001,
+    PriceGreaterThanMaxPrice = -4002,
+    QtyLessThanZero = -4003,
+    QtyLessThanMinQty = -4004,
+    QtyGreaterThanMaxQty = -4005,
+    StopPriceLessThanZero = -4006,
+    StopPriceGreaterThanMaxPrice = -4007,
+    PriceNotIncreasedByTickSize = -4014,
+    InvalidClOrdIdLen = -4015,
+    PriceHigherThanMultiplierUp = -4016,
+    QtyNotIncreasedByStepSize = -4023,
+    PriceLowerThanMultiplierDown = -4024,
+    InvalidLeverage = -4028,
+    NoNeedToChangeMarginType = -4046,
+    NoNeedToChangePositionSide = -4059,
+    PositionSideNotMatch = -4061,
+    MarketOrderReject = -4131,
+    /// Order notional below the symbol's minimum notional
+    MinNotional = -4164,
+
+    // 50xx - Order execution issues
+    FokOrderReject = -5021,
+    GtxOrderReject = -5022,
+}
+
+impl BinanceErrorCode {
+    /// The request was not processed for a transient reason and can be sent again.
+    pub fn is_retryable(self) -> bool {
+        matches!(
+            self,
+            BinanceErrorCode::Disconnected
+                | BinanceErrorCode::TooManyRequests
+                | BinanceErrorCode::UnexpectedResponse
+                | BinanceErrorCode::Timeout
+                | BinanceErrorCode::ServerBusy
+                | BinanceErrorCode::TooManyOrders
+        )
+    }
+
+    /// The local clock is out of sync with the server (-1021).
+    pub fn is_timestamp_error(self) -> bool {
+        self == BinanceErrorCode::InvalidTimestamp
+    }
+
+    /// The order was rejected by a symbol filter (tick size, step size, quantity, notional).
+    pub fn is_filter_error(self) -> bool {
+        matches!(
+            self,
+            BinanceErrorCode::InvalidMessage
+                | BinanceErrorCode::BadPrecision
+                | BinanceErrorCode::PriceLessThanZero
+                | BinanceErrorCode::PriceGreaterThanMaxPrice
+                | BinanceErrorCode::QtyLessThanZero
+                | BinanceErrorCode::QtyLessThanMinQty
+                | BinanceErrorCode::QtyGreaterThanMaxQty
+                | BinanceErrorCode::StopPriceLessThanZero
+                | BinanceErrorCode::StopPriceGreaterThanMaxPrice
+                | BinanceErrorCode::PriceNotIncreasedByTickSize
+                | BinanceErrorCode::PriceHigherThanMultiplierUp
+                | BinanceErrorCode::QtyNotIncreasedByStepSize
+                | BinanceErrorCode::PriceLowerThanMultiplierDown
+                | BinanceErrorCode::MinNotional
+        )
+    }
+}
+
 error_chain! {
     errors {
         BinanceError(response: BinanceContentError)
+
+        RateLimitBudgetExceeded(usage: RateLimitUsage) {
+            description("rate limit budget exceeded")
+            display("Rate limit budget exceeded: {:?} used {} of {:?} in {:?}",
+                usage.rate_limit_type, usage.used, usage.limit, usage.interval)
+        }
+
+        InvalidOrder(symbol: String, reason: String) {
+            description("invalid order")
+            display("Invalid order for {}: {}", symbol, reason)
+        }
+
+        InvalidEvent(event_type: String, reason: String) {
+            description("invalid websocket event")
+            display("Invalid {} event: {}", event_type, reason)
+        }
+
+        StaleConnection(silence: Duration) {
+            description("stale websocket connection")
+            display("No data received on the websocket for {:?}", silence)
+        }
+
+        TooManyRequests(retry_after: Option<Duration>) {
+            description("too many requests")
+            display("Too many requests (HTTP 429), retry after {:?}", retry_after)
+        }
+
+        IpBanned(retry_after: Option<Duration>) {
+            description("IP banned")
+            display("IP banned for exceeding the rate limits (HTTP 418), retry after {:?}", retry_after)
+        }
      }
 
     foreign_links {
@@ -26,3 +229,13 @@ error_chain! {
         TimestampError(std::time::SystemTimeError);
     }
 }
+
+impl Error {
+    /// Error code sent by Binance, if the request was rejected by the exchange.
+    pub fn binance_error_code(&self) -> Option<BinanceErrorCode> {
+        match self.0 {
+            ErrorKind::BinanceError(ref response) => Some(response.error_code()),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use crate::config::Config;
+use crate::errors::*;
+use crate::streams::StreamName;
+use crate::websockets::{ShutdownHandle, WebSockets, WebsocketEvent};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+/// What the socket thread does when the event buffer is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropPolicy {
+    /// Waits for the consumer, which delays the reading of the socket.
+    Block,
+    /// Drops the new event, counted by `WebSocketHandle::dropped_events`.
+    DropNewest,
+}
+
+/// Runs a `WebSockets` connection on a background thread and sends its events to an
+/// `mpsc::Receiver`, instead of passing them to a callback.
+///
+/// The receiver can be iterated, it ends once the connection stopped.
+#[derive(Clone)]
+pub struct EventChannel {
+    buffer: usize,
+    drop_policy: DropPolicy,
+    config: Config,
+}
+
+impl EventChannel {
+    /// Channel buffering up to `buffer` events, blocking the socket thread when full.
+    pub fn new(buffer: usize) -> Self {
+        EventChannel {
+            buffer,
+            drop_policy: DropPolicy::Block,
+            config: Config::default(),
+        }
+    }
+
+    pub fn set_drop_policy(mut self, drop_policy: DropPolicy) -> Self {
+        self.drop_policy = drop_policy;
+        self
+    }
+
+    pub fn set_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Connects like `WebSockets::connect`, the handshake error is returned here.
+    pub fn connect(&self, endpoint: &str) -> Result<(Receiver<WebsocketEvent>, WebSocketHandle)> {
+        self.spawn(endpoint.to_string())
+    }
+
+    pub fn connect_streams(
+        &self, streams: &[StreamName],
+    ) -> Result<(Receiver<WebsocketEvent>, WebSocketHandle)> {
+        self.spawn(StreamName::combined_endpoint(streams))
+    }
+
+    fn spawn(&self, endpoint: String) -> Result<(Receiver<WebsocketEvent>, WebSocketHandle)> {
+        let (sender, receiver) = mpsc::sync_channel(self.buffer);
+        let (connected_sender, connected) = mpsc::channel();
+        let running = Arc::new(AtomicBool::new(true));
+        let dropped = Arc::new(AtomicU64::new(0));
+        let shutdown = ShutdownHandle::default();
+
+        let forwarder = Forwarder {
+            sender,
+            drop_policy: self.drop_policy,
+            running: running.clone(),
+            dropped: dropped.clone(),
+            shutdown: shutdown.clone(),
+        };
+        let config = self.config.clone();
+        let thread_running = running.clone();
+
+        let thread = thread::spawn(move || {
+            let mut web_socket =
+                WebSockets::new_with_config(move |event| forwarder.send(event), config)
+                    .set_shutdown_handle(shutdown);
+
+            let result = web_socket.connect(&endpoint);
+            let failed = result.is_err();
+            let _ = connected_sender.send(result.map(|()| web_socket.shutdown_handle()));
+            if failed {
+                return Ok(());
+            }
+
+            let result = web_socket.event_loop(&thread_running);
+            let _ = web_socket.disconnect();
+            thread_running.store(false, Ordering::Relaxed);
+            result
+        });
+
+        let shutdown = match connected.recv() {
+            Ok(Ok(shutdown)) => shutdown,
+            Ok(Err(e)) => return Err(e),
+            Err(_) => bail!("Websocket thread panicked"),
+        };
+
+        let handle = WebSocketHandle {
+            running,
+            shutdown,
+            dropped,
+            thread: Some(thread),
+        };
+        Ok((receiver, handle))
+    }
+}
+
+struct Forwarder {
+    sender: SyncSender<WebsocketEvent>,
+    drop_policy: DropPolicy,
+    running: Arc<AtomicBool>,
+    dropped: Arc<AtomicU64>,
+    shutdown: ShutdownHandle,
+}
+
+impl Forwarder {
+    fn send(&self, event: WebsocketEvent) -> Result<()> {
+        let disconnected = match self.drop_policy {
+            DropPolicy::Block => self.sender.send(event).is_err(),
+            DropPolicy::DropNewest => match self.sender.try_send(event) {
+                Ok(()) => false,
+                Err(TrySendError::Full(_)) => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    false
+                }
+                Err(TrySendError::Disconnected(_)) => true,
+            },
+        };
+        // Nobody listens anymore, stop without waiting for the next message
+        if disconnected {
+            self.running.store(false, Ordering::Relaxed);
+            self.shutdown.shutdown();
+        }
+
+        Ok(())
+    }
+}
+
+/// Controls the socket thread of an `EventChannel`. Dropping the handle stops the thread
+/// without waiting for it.
+pub struct WebSocketHandle {
+    running: Arc<AtomicBool>,
+    shutdown: ShutdownHandle,
+    dropped: Arc<AtomicU64>,
+    thread: Option<JoinHandle<Result<()>>>,
+}
+
+impl WebSocketHandle {
+    /// Stops the thread, without waiting for the next message, see `ShutdownHandle`.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::Relaxed);
+        self.shutdown.shutdown();
+    }
+
+    /// False once stopped, or once the connection failed or the receiver was dropped.
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed) && !self.shutdown.is_shutdown()
+    }
+
+    /// Handle stopping the thread, which can be cloned to other threads.
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown.clone()
+    }
+
+    /// Events dropped with `DropPolicy::DropNewest` because the buffer was full.
+    pub fn dropped_events(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Stops the thread and waits for it, returning the error which ended the connection.
+    ///
+    /// With `DropPolicy::Block` the thread may wait on a full buffer: keep receiving,
+    /// or drop the receiver, before joining.
+    pub fn join(mut self) -> Result<()> {
+        self.stop();
+        match self.thread.take().unwrap().join() {
+            Ok(result) => result,
+            Err(_) => bail!("Websocket thread panicked"),
+        }
+    }
+}
+
+impl Drop for WebSocketHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::net::TcpListener;
+    use std::time::{Duration, Instant};
+    use tungstenite::Message;
+
+    // Local server sending `trades` trades, then one more when `next` receives
+    fn serve(trades: u64) -> (Config, mpsc::Sender<()>, JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (next, next_trade) = mpsc::channel::<()>();
+
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut socket = tungstenite::accept(stream).unwrap();
+            for id in 1..=trades + 1 {
+                if id > trades && next_trade.recv().is_err() {
+                    break;
+                }
+                let data = json!({
+                    "e": "aggTrade", "E": 1, "s": "BNBBTC", "a": id, "p": "0.001", "q": "100",
+                    "f": 100, "l": 105, "T": 1, "m": true, "M": true
+                });
+                let message = json!({ "stream": "bnbbtc@aggTrade", "data": data });
+                socket.write_message(Message::Text(message.to_string())).unwrap();
+            }
+            while socket.read_message().is_ok() {}
+        });
+        let config = Config::default().set_ws_endpoint(format!("ws://127.0.0.1:{}", port));
+
+        (config, next, server)
+    }
+
+    fn wait_for<F: Fn() -> bool>(condition: F) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !condition() {
+            assert!(Instant::now() < deadline, "timed out");
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    fn trade_id(event: WebsocketEvent) -> u64 {
+        match event {
+            WebsocketEvent::Trade(trade) => trade.aggregated_trade_id,
+            _ => panic!("expected Trade"),
+        }
+    }
+
+    #[test]
+    fn block_keeps_every_event() {
+        let (config, next, server) = serve(3);
+        let (receiver, handle) = EventChannel::new(1)
+            .set_config(config)
+            .connect("bnbbtc@aggTrade")
+            .unwrap();
+
+        assert!(matches!(receiver.recv().unwrap(), WebsocketEvent::Connected));
+        for id in 1..=3 {
+            assert_eq!(trade_id(receiver.recv().unwrap()), id);
+        }
+        assert_eq!(handle.dropped_events(), 0);
+
+        // The next event finds the receiver dropped and stops the thread
+        drop(receiver);
+        next.send(()).unwrap();
+        wait_for(|| handle.shutdown_handle().is_shutdown());
+        assert!(!handle.is_running());
+        handle.join().unwrap();
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn drop_newest_counts_dropped_events() {
+        let (config, next, server) = serve(5);
+        let (receiver, handle) = EventChannel::new(1)
+            .set_drop_policy(DropPolicy::DropNewest)
+            .set_config(config)
+            .connect("bnbbtc@aggTrade")
+            .unwrap();
+
+        // `Connected` filled the buffer
+        wait_for(|| handle.dropped_events() == 5);
+        assert!(matches!(receiver.recv().unwrap(), WebsocketEvent::Connected));
+        next.send(()).unwrap();
+        assert_eq!(trade_id(receiver.recv().unwrap()), 6);
+        assert!(handle.is_running());
+
+        drop(receiver);
+        handle.join().unwrap();
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn drop_newest_stops_once_the_receiver_is_dropped() {
+        let (config, next, server) = serve(0);
+        let (receiver, handle) = EventChannel::new(1)
+            .set_drop_policy(DropPolicy::DropNewest)
+            .set_config(config)
+            .connect("bnbbtc@aggTrade")
+            .unwrap();
+
+        drop(receiver);
+        next.send(()).unwrap();
+        wait_for(|| handle.shutdown_handle().is_shutdown());
+        handle.join().unwrap();
+        server.join().unwrap();
+    }
+}
@@ -1,7 +1,9 @@
 use crate::futures::model::*;
 use crate::client::*;
 use crate::errors::*;
+use crate::registry::FuturesSymbolRegistry;
 use serde_json::from_str;
+use std::time::Duration;
 
 #[derive(Clone)]
 pub struct FuturesGeneral {
@@ -28,10 +30,18 @@ impl FuturesGeneral {
     pub fn exchange_info(&self) -> Result<ExchangeInformation> {
         let data: String = self.client.get("/fapi/v1/exchangeInfo", "")?;
         let info: ExchangeInformation = from_str(data.as_str())?;
+        self.client.rate_limiter().set_limits(&info.rate_limits);
 
         Ok(info)
     }
 
+    // Symbol registry downloading the exchange information through this client
+    // - Cached for `ttl`, instead of once per lookup like `get_symbol_info`
+    pub fn symbol_registry(&self, ttl: Duration) -> FuturesSymbolRegistry {
+        let general = self.clone();
+        FuturesSymbolRegistry::with_loader(ttl, move || general.exchange_info())
+    }
+
     // Get Symbol information
     pub fn get_symbol_info<S>(&self, symbol: S) -> Result<Symbol>
     where
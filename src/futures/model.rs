@@ -1,8 +1,9 @@
 use crate::model::string_or_float;
 
 pub use crate::model::{
-    Asks, Bids, BookTickers, Filters, KlineSummaries, KlineSummary, RateLimit, ServerTime,
-    SymbolPrice, Tickers,
+    Asks, Bids, BookTickers, ContractType, Filters, KlineInterval, KlineSummaries, KlineSummary,
+    Num, NumString, OrderSide, OrderStatus, OrderType, PositionSide, RateLimit, ServerTime,
+    SymbolPrice, Tickers, TimeInForce, WorkingType,
 };
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -20,8 +21,10 @@ pub struct ExchangeInformation {
 pub struct Symbol {
     pub symbol: String,
     pub status: String,
-    pub maint_margin_percent: String,
-    pub required_margin_percent: String,
+    #[serde(with = "string_or_float")]
+    pub maint_margin_percent: NumString,
+    #[serde(with = "string_or_float")]
+    pub required_margin_percent: NumString,
     pub base_asset: String,
     pub quote_asset: String,
     pub price_precision: u16,
@@ -29,8 +32,8 @@ pub struct Symbol {
     pub base_asset_precision: u64,
     pub quote_precision: u64,
     pub filters: Vec<Filters>,
-    pub order_types: Vec<String>,
-    pub time_in_force: Vec<String>,
+    pub order_types: Vec<OrderType>,
+    pub time_in_force: Vec<TimeInForce>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -51,23 +54,26 @@ pub struct OrderBook {
 #[serde(rename_all = "camelCase")]
 pub struct PriceStats {
     pub symbol: String,
-    pub price_change: String,
-    pub price_change_percent: String,
-    pub weighted_avg_price: String,
     #[serde(with = "string_or_float")]
-    pub last_price: f64,
+    pub price_change: NumString,
+    #[serde(with = "string_or_float")]
+    pub price_change_percent: NumString,
+    #[serde(with = "string_or_float")]
+    pub weighted_avg_price: NumString,
     #[serde(with = "string_or_float")]
-    pub open_price: f64,
+    pub last_price: Num,
     #[serde(with = "string_or_float")]
-    pub high_price: f64,
+    pub open_price: Num,
     #[serde(with = "string_or_float")]
-    pub low_price: f64,
+    pub high_price: Num,
     #[serde(with = "string_or_float")]
-    pub volume: f64,
+    pub low_price: Num,
     #[serde(with = "string_or_float")]
-    pub quote_volume: f64,
+    pub volume: Num,
     #[serde(with = "string_or_float")]
-    pub last_qty: f64,
+    pub quote_volume: Num,
+    #[serde(with = "string_or_float")]
+    pub last_qty: Num,
     pub open_time: u64,
     pub close_time: u64,
     pub first_id: u64,
@@ -87,11 +93,11 @@ pub struct Trade {
     pub id: u64,
     pub is_buyer_maker: bool,
     #[serde(with = "string_or_float")]
-    pub price: f64,
+    pub price: Num,
     #[serde(with = "string_or_float")]
-    pub qty: f64,
+    pub qty: Num,
     #[serde(with = "string_or_float")]
-    pub quote_qty: f64,
+    pub quote_qty: Num,
     pub time: u64,
 }
 
@@ -115,9 +121,9 @@ pub struct AggTrade {
     #[serde(rename = "m")]
     pub maker: bool,
     #[serde(rename = "p", with = "string_or_float")]
-    pub price: f64,
+    pub price: Num,
     #[serde(rename = "q", with = "string_or_float")]
-    pub qty: f64,
+    pub qty: Num,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -131,9 +137,9 @@ pub enum MarkPrices {
 pub struct MarkPrice {
     pub symbol: String,
     #[serde(with = "string_or_float")]
-    pub mark_price: f64,
+    pub mark_price: Num,
     #[serde(with = "string_or_float")]
-    pub last_funding_rate: f64,
+    pub last_funding_rate: Num,
     pub next_funding_time: u64,
     pub time: u64,
 }
@@ -148,25 +154,70 @@ pub enum LiquidationOrders {
 #[serde(rename_all = "camelCase")]
 pub struct LiquidationOrder {
     #[serde(with = "string_or_float")]
-    pub average_price: f64,
+    pub average_price: Num,
     #[serde(with = "string_or_float")]
-    pub executed_qty: f64,
+    pub executed_qty: Num,
     #[serde(with = "string_or_float")]
-    pub orig_qty: f64,
+    pub orig_qty: Num,
     #[serde(with = "string_or_float")]
-    pub price: f64,
-    pub side: String,
-    pub status: String,
+    pub price: Num,
+    pub side: OrderSide,
+    pub status: OrderStatus,
     pub symbol: String,
     pub time: u64,
-    pub time_in_force: String,
-    pub r#type: String,
+    pub time_in_force: TimeInForce,
+    pub r#type: OrderType,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct OpenInterest {
     #[serde(with = "string_or_float")]
-    pub open_interest: f64,
+    pub open_interest: Num,
     pub symbol: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    const PRICE_STATS: &str = r#"{
+        "symbol": "BTCUSDT", "priceChange": "-94.99999800", "priceChangePercent": "-95.960",
+        "weightedAvgPrice": "0.29628482", "lastPrice": "4", "lastQty": "200",
+        "openPrice": "99", "highPrice": "100", "lowPrice": "0.1", "volume": "8913",
+        "quoteVolume": "15.3", "openTime": 1499783499040, "closeTime": 1499869899040,
+        "firstId": 28385, "lastId": 28460, "count": 76
+    }"#;
+
+    #[test]
+    fn price_stats_round_trip() {
+        let stats: PriceStats = serde_json::from_str(PRICE_STATS).unwrap();
+        let expected: Value = serde_json::from_str(PRICE_STATS).unwrap();
+        assert_eq!(serde_json::to_value(&stats).unwrap(), expected);
+
+        assert_eq!(stats.price_change.to_string(), "-94.99999800");
+        assert_eq!(stats.low_price.to_string(), "0.1");
+    }
+
+    #[cfg(not(feature = "decimal"))]
+    #[test]
+    fn price_change_stays_a_string() {
+        let stats: PriceStats = serde_json::from_str(PRICE_STATS).unwrap();
+        let price_change: &String = &stats.price_change;
+
+        assert_eq!(price_change, "-94.99999800");
+        assert_eq!(stats.last_price, 4.0);
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn price_change_is_a_decimal() {
+        use std::str::FromStr;
+
+        let stats: PriceStats = serde_json::from_str(PRICE_STATS).unwrap();
+
+        assert_eq!(stats.price_change, Num::from_str("-94.999998").unwrap());
+        assert_eq!(stats.weighted_avg_price, Num::from_str("0.29628482").unwrap());
+    }
+}
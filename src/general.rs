@@ -1,8 +1,10 @@
 use crate::model::*;
 use crate::client::*;
 use crate::errors::*;
+use crate::registry::SpotSymbolRegistry;
 
 use serde_json::from_str;
+use std::time::Duration;
 
 #[derive(Clone)]
 pub struct General {
@@ -32,10 +34,18 @@ impl General {
         let data: String = self.client.get("/api/v3/exchangeInfo", "")?;
 
         let info: ExchangeInformation = from_str(data.as_str())?;
+        self.client.rate_limiter().set_limits(&info.rate_limits);
 
         Ok(info)
     }
 
+    // Symbol registry downloading the exchange information through this client
+    // - Cached for `ttl`, instead of once per lookup like `get_symbol_info`
+    pub fn symbol_registry(&self, ttl: Duration) -> SpotSymbolRegistry {
+        let general = self.clone();
+        SpotSymbolRegistry::with_loader(ttl, move || general.exchange_info())
+    }
+
     // Get Symbol information
     pub fn get_symbol_info<S>(&self, symbol: S) -> Result<Symbol>
     where
@@ -12,8 +12,16 @@ extern crate error_chain;
 #[macro_use]
 extern crate serde_derive;
 
-mod client;
+pub mod client;
+pub mod config;
 pub mod errors;
+pub mod eventchannel;
+pub mod orderbook;
+pub mod ratelimit;
+pub mod registry;
+pub mod streams;
+pub mod symbolrules;
+pub mod timesync;
 mod util;
 
 pub mod model;
@@ -24,5 +32,9 @@ pub mod general;
 pub mod market;
 pub mod userstream;
 pub mod websockets;
+pub mod wsapi;
 
 pub mod futures;
+
+#[cfg(feature = "async")]
+pub mod asynchronous;
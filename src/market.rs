@@ -2,7 +2,6 @@ use crate::util::*;
 use crate::model::*;
 use crate::client::*;
 use crate::errors::*;
-use std::collections::BTreeMap;
 use serde_json::{Value, from_str};
 
 #[derive(Clone)]
@@ -18,10 +17,7 @@ impl Market {
     where
         S: Into<String>,
     {
-        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
-
-        parameters.insert("symbol".into(), symbol.into());
-        let request = build_request(&parameters);
+        let request = build_symbol_request(symbol.into());
 
         let data = self.client.get("/api/v3/depth", &request)?;
 
@@ -44,10 +40,7 @@ impl Market {
     where
         S: Into<String>,
     {
-        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
-
-        parameters.insert("symbol".into(), symbol.into());
-        let request = build_request(&parameters);
+        let request = build_symbol_request(symbol.into());
 
         let data = self.client.get("/api/v3/ticker/price", &request)?;
         let symbol_price: SymbolPrice = from_str(data.as_str())?;
@@ -60,10 +53,7 @@ impl Market {
     where
         S: Into<String>,
     {
-        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
-
-        parameters.insert("symbol".into(), symbol.into());
-        let request = build_request(&parameters);
+        let request = build_symbol_request(symbol.into());
 
         let data = self.client.get("/api/v3/avgPrice", &request)?;
         let average_price: AveragePrice = from_str(data.as_str())?;
@@ -86,10 +76,7 @@ impl Market {
     where
         S: Into<String>,
     {
-        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
-
-        parameters.insert("symbol".into(), symbol.into());
-        let request = build_request(&parameters);
+        let request = build_symbol_request(symbol.into());
 
         let data = self.client.get("/api/v3/ticker/bookTicker", &request)?;
         let ticker: Tickers = from_str(data.as_str())?;
@@ -102,10 +89,7 @@ impl Market {
     where
         S: Into<String>,
     {
-        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
-
-        parameters.insert("symbol".into(), symbol.into());
-        let request = build_request(&parameters);
+        let request = build_symbol_request(symbol.into());
 
         let data = self.client.get("/api/v3/ticker/24hr", &request)?;
 
@@ -114,57 +98,30 @@ impl Market {
         Ok(stats)
     }
 
-    // Returns up to 'limit' klines for given symbol and interval ("1m", "5m", ...)
+    // Returns up to 'limit' klines for given symbol and interval (KlineInterval::Minutes1, "5m", ...)
     // https://github.com/binance-exchange/binance-official-api-docs/blob/master/rest-api.md#klinecandlestick-data
     pub fn get_klines<S1, S2, S3, S4, S5>(
         &self, symbol: S1, interval: S2, limit: S3, start_time: S4, end_time: S5,
     ) -> Result<KlineSummaries>
     where
         S1: Into<String>,
-        S2: Into<String>,
+        S2: Into<KlineInterval>,
         S3: Into<Option<u16>>,
         S4: Into<Option<u64>>,
         S5: Into<Option<u64>>,
     {
-        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
-
-        parameters.insert("symbol".into(), symbol.into());
-        parameters.insert("interval".into(), interval.into());
-
-        // Add three optional parameters
-        if let Some(lt) = limit.into() {
-            parameters.insert("limit".into(), format!("{}", lt));
-        }
-        if let Some(st) = start_time.into() {
-            parameters.insert("startTime".into(), format!("{}", st));
-        }
-        if let Some(et) = end_time.into() {
-            parameters.insert("endTime".into(), format!("{}", et));
-        }
-
-        let request = build_request(&parameters);
+        let request = build_klines_request(
+            symbol.into(),
+            interval.into(),
+            limit.into(),
+            start_time.into(),
+            end_time.into(),
+        );
 
         let data = self.client.get("/api/v3/klines", &request)?;
         let parsed_data: Vec<Vec<Value>> = from_str(data.as_str())?;
 
-        let klines = KlineSummaries::AllKlineSummaries(
-            parsed_data
-                .iter()
-                .map(|row| KlineSummary {
-                    open_time: to_i64(&row[0]),
-                    open: to_f64(&row[1]),
-                    high: to_f64(&row[2]),
-                    low: to_f64(&row[3]),
-                    close: to_f64(&row[4]),
-                    volume: to_f64(&row[5]),
-                    close_time: to_i64(&row[6]),
-                    quote_asset_volume: to_f64(&row[7]),
-                    number_of_trades: to_i64(&row[8]),
-                    taker_buy_base_asset_volume: to_f64(&row[9]),
-                    taker_buy_quote_asset_volume: to_f64(&row[10]),
-                })
-                .collect(),
-        );
+        let klines = to_kline_summaries(&parsed_data);
         Ok(klines)
     }
 }
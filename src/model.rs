@@ -1,3 +1,20 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// Prices and quantities: `f64` by default, `rust_decimal::Decimal` with the `decimal`
+/// feature, for exact arithmetic and order parameters without float artifacts.
+#[cfg(not(feature = "decimal"))]
+pub type Num = f64;
+#[cfg(feature = "decimal")]
+pub type Num = rust_decimal::Decimal;
+
+/// Amounts the exchange sends as strings which the models keep as `String` by default,
+/// and parse as `rust_decimal::Decimal` with the `decimal` feature.
+#[cfg(not(feature = "decimal"))]
+pub type NumString = String;
+#[cfg(feature = "decimal")]
+pub type NumString = rust_decimal::Decimal;
+
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ServerTime {
@@ -31,7 +48,7 @@ pub struct Symbol {
     pub base_asset_precision: u64,
     pub quote_asset: String,
     pub quote_precision: u64,
-    pub order_types: Vec<String>,
+    pub order_types: Vec<OrderType>,
     pub iceberg_allowed: bool,
     pub is_spot_trading_allowed: bool,
     pub is_margin_trading_allowed: bool,
@@ -44,29 +61,39 @@ pub enum Filters {
     #[serde(rename = "PRICE_FILTER")]
     #[serde(rename_all = "camelCase")]
     PriceFilter {
-        min_price: String,
-        max_price: String,
-        tick_size: String,
+        #[serde(with = "string_or_float")]
+        min_price: NumString,
+        #[serde(with = "string_or_float")]
+        max_price: NumString,
+        #[serde(with = "string_or_float")]
+        tick_size: NumString,
     },
     #[serde(rename = "PERCENT_PRICE")]
     #[serde(rename_all = "camelCase")]
     PercentPrice {
-        multiplier_up: String,
-        multiplier_down: String,
+        #[serde(with = "string_or_float")]
+        multiplier_up: NumString,
+        #[serde(with = "string_or_float")]
+        multiplier_down: NumString,
         avg_price_mins: Option<f64>,
     },
     #[serde(rename = "LOT_SIZE")]
     #[serde(rename_all = "camelCase")]
     LotSize {
-        min_qty: String,
-        max_qty: String,
-        step_size: String,
+        #[serde(with = "string_or_float")]
+        min_qty: NumString,
+        #[serde(with = "string_or_float")]
+        max_qty: NumString,
+        #[serde(with = "string_or_float")]
+        step_size: NumString,
     },
     #[serde(rename = "MIN_NOTIONAL")]
     #[serde(rename_all = "camelCase")]
     MinNotional {
-        min_notional: String,
-        apply_to_market: bool,
+        // Sent as `notional` by the futures API
+        #[serde(alias = "notional", with = "string_or_float")]
+        min_notional: NumString,
+        apply_to_market: Option<bool>,
         avg_price_mins: Option<f64>,
     },
     #[serde(rename = "ICEBERG_PARTS")]
@@ -83,13 +110,19 @@ pub enum Filters {
     MaxNumIcebergOrders { max_num_iceberg_orders: u16 },
     #[serde(rename = "MAX_POSITION")]
     #[serde(rename_all = "camelCase")]
-    MaxPosition { max_position: String },
+    MaxPosition {
+        #[serde(with = "string_or_float")]
+        max_position: NumString,
+    },
     #[serde(rename = "MARKET_LOT_SIZE")]
     #[serde(rename_all = "camelCase")]
     MarketLotSize {
-        min_qty: String,
-        max_qty: String,
-        step_size: String,
+        #[serde(with = "string_or_float")]
+        min_qty: NumString,
+        #[serde(with = "string_or_float")]
+        max_qty: NumString,
+        #[serde(with = "string_or_float")]
+        step_size: NumString,
     },
 }
 
@@ -110,8 +143,10 @@ pub struct AccountInformation {
 #[serde(rename_all = "camelCase")]
 pub struct Balance {
     pub asset: String,
-    pub free: String,
-    pub locked: String,
+    #[serde(with = "string_or_float")]
+    pub free: NumString,
+    #[serde(with = "string_or_float")]
+    pub locked: NumString,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -121,17 +156,20 @@ pub struct Order {
     pub order_id: u64,
     pub client_order_id: String,
     #[serde(with = "string_or_float")]
-    pub price: f64,
-    pub orig_qty: String,
-    pub executed_qty: String,
-    pub status: String,
-    pub time_in_force: String,
+    pub price: Num,
+    #[serde(with = "string_or_float")]
+    pub orig_qty: NumString,
+    #[serde(with = "string_or_float")]
+    pub executed_qty: NumString,
+    pub status: OrderStatus,
+    pub time_in_force: TimeInForce,
     #[serde(rename = "type")]
-    pub type_name: String,
-    pub side: String,
+    pub type_name: OrderType,
+    pub side: OrderSide,
     #[serde(with = "string_or_float")]
-    pub stop_price: f64,
-    pub iceberg_qty: String,
+    pub stop_price: Num,
+    #[serde(with = "string_or_float")]
+    pub iceberg_qty: NumString,
     pub time: u64,
 }
 
@@ -145,11 +183,11 @@ pub struct OrderCanceled {
     pub client_order_id: String,
     pub update_time: u64,
     #[serde(with = "string_or_float")]
-    pub cum_qty: f64,
+    pub cum_qty: Num,
     #[serde(with = "string_or_float")]
-    pub orig_qty: f64,
+    pub orig_qty: Num,
     #[serde(with = "string_or_float")]
-    pub price: f64,
+    pub price: Num,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -160,16 +198,16 @@ pub struct Transaction {
     pub client_order_id: String,
     pub transact_time: u64,
     #[serde(with = "string_or_float")]
-    pub price: f64,
+    pub price: Num,
     #[serde(with = "string_or_float")]    
-    pub orig_qty: f64,
+    pub orig_qty: Num,
     #[serde(with = "string_or_float")]    
-    pub executed_qty: f64,
+    pub executed_qty: Num,
     #[serde(with = "string_or_float")]    
-    pub cummulative_quote_qty: f64,
-    pub status: String,
-    pub time_in_force: String,
-    pub side: String,
+    pub cummulative_quote_qty: Num,
+    pub status: OrderStatus,
+    pub time_in_force: TimeInForce,
+    pub side: OrderSide,
     pub fills: Vec<FillInfo>,
 }
 
@@ -177,11 +215,11 @@ pub struct Transaction {
 #[serde(rename_all = "camelCase")]
 pub struct FillInfo {
     #[serde(with = "string_or_float")]
-    pub price: f64,
+    pub price: Num,
     #[serde(with = "string_or_float")]    
-    pub qty: f64,
+    pub qty: Num,
     #[serde(with = "string_or_float")]    
-    pub commission: f64,
+    pub commission: Num,
     pub commission_asset: String,
     pub trade_id: Option<u64>,
 }
@@ -204,11 +242,12 @@ pub struct OrderBook {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Bids {
     #[serde(with = "string_or_float")]
-    pub price: f64,
+    pub price: Num,
     #[serde(with = "string_or_float")]
-    pub qty: f64,
+    pub qty: Num,
 
     // Never serialized.
+    #[allow(dead_code)]
     #[serde(skip)]
     ignore: Vec<String>,
 }
@@ -216,11 +255,12 @@ pub struct Bids {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Asks {
     #[serde(with = "string_or_float")]
-    pub price: f64,
+    pub price: Num,
     #[serde(with = "string_or_float")]
-    pub qty: f64,
+    pub qty: Num,
 
     // Never serialized.
+    #[allow(dead_code)]
     #[serde(skip)]
     ignore: Vec<String>,
 }
@@ -245,14 +285,14 @@ pub enum Prices {
 pub struct SymbolPrice {
     pub symbol: String,
     #[serde(with = "string_or_float")]
-    pub price: f64,
+    pub price: Num,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AveragePrice {
     pub mins: u64,
     #[serde(with = "string_or_float")]
-    pub price: f64,
+    pub price: Num,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -272,13 +312,13 @@ pub enum KlineSummaries {
 pub struct Tickers {
     pub symbol: String,
     #[serde(with = "string_or_float")]
-    pub bid_price: f64,
+    pub bid_price: Num,
     #[serde(with = "string_or_float")]
-    pub bid_qty: f64,
+    pub bid_qty: Num,
     #[serde(with = "string_or_float")]
-    pub ask_price: f64,
+    pub ask_price: Num,
     #[serde(with = "string_or_float")]
-    pub ask_qty: f64,
+    pub ask_qty: Num,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -286,10 +326,11 @@ pub struct Tickers {
 pub struct TradeHistory {
     pub id: u64,
     #[serde(with = "string_or_float")]
-    pub price: f64,
+    pub price: Num,
+    #[serde(with = "string_or_float")]
+    pub qty: Num,
     #[serde(with = "string_or_float")]
-    pub qty: f64,
-    pub commission: String,
+    pub commission: NumString,
     pub commission_asset: String,
     pub time: u64,
     pub is_buyer: bool,
@@ -300,25 +341,28 @@ pub struct TradeHistory {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct PriceStats {
-    pub price_change: String,
-    pub price_change_percent: String,
-    pub weighted_avg_price: String,
     #[serde(with = "string_or_float")]
-    pub prev_close_price: f64,
+    pub price_change: NumString,
+    #[serde(with = "string_or_float")]
+    pub price_change_percent: NumString,
+    #[serde(with = "string_or_float")]
+    pub weighted_avg_price: NumString,
+    #[serde(with = "string_or_float")]
+    pub prev_close_price: Num,
     #[serde(with = "string_or_float")]
-    pub last_price: f64,
+    pub last_price: Num,
     #[serde(with = "string_or_float")]
-    pub bid_price: f64,
+    pub bid_price: Num,
     #[serde(with = "string_or_float")]
-    pub ask_price: f64,
+    pub ask_price: Num,
     #[serde(with = "string_or_float")]
-    pub open_price: f64,
+    pub open_price: Num,
     #[serde(with = "string_or_float")]
-    pub high_price: f64,
+    pub high_price: Num,
     #[serde(with = "string_or_float")]
-    pub low_price: f64,
+    pub low_price: Num,
     #[serde(with = "string_or_float")]
-    pub volume: f64,
+    pub volume: Num,
     pub open_time: u64,
     pub close_time: u64,
     pub first_id: u64,
@@ -356,10 +400,10 @@ pub struct AccountUpdateEvent {
 pub struct EventBalance {
     #[serde(rename = "a")]
     pub asset: String,
-    #[serde(rename = "f")]
-    pub free: String,
-    #[serde(rename = "l")]
-    pub locked: String,
+    #[serde(rename = "f", with = "string_or_float")]
+    pub free: NumString,
+    #[serde(rename = "l", with = "string_or_float")]
+    pub locked: NumString,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -378,19 +422,19 @@ pub struct OrderTradeEvent {
     pub new_client_order_id: String,
 
     #[serde(rename = "S")]
-    pub side: String,
+    pub side: OrderSide,
 
     #[serde(rename = "o")]
-    pub order_type: String,
+    pub order_type: OrderType,
 
     #[serde(rename = "f")]
-    pub time_in_force: String,
+    pub time_in_force: TimeInForce,
 
-    #[serde(rename = "q")]
-    pub qty: String,
+    #[serde(rename = "q", with = "string_or_float")]
+    pub qty: NumString,
 
-    #[serde(rename = "p")]
-    pub price: String,
+    #[serde(rename = "p", with = "string_or_float")]
+    pub price: NumString,
 
     #[serde(skip, rename = "P")]
     pub p_ignore: String,
@@ -405,10 +449,10 @@ pub struct OrderTradeEvent {
     pub c_ignore: Option<String>,
 
     #[serde(rename = "x")]
-    pub execution_type: String,
+    pub execution_type: ExecutionType,
 
     #[serde(rename = "X")]
-    pub order_status: String,
+    pub order_status: OrderStatus,
 
     #[serde(rename = "r")]
     pub order_reject_reason: String,
@@ -416,17 +460,17 @@ pub struct OrderTradeEvent {
     #[serde(rename = "i")]
     pub order_id: u64,
 
-    #[serde(rename = "l")]
-    pub qty_last_filled_trade: String,
+    #[serde(rename = "l", with = "string_or_float")]
+    pub qty_last_filled_trade: NumString,
 
-    #[serde(rename = "z")]
-    pub accumulated_qty_filled_trades: String,
+    #[serde(rename = "z", with = "string_or_float")]
+    pub accumulated_qty_filled_trades: NumString,
 
-    #[serde(rename = "L")]
-    pub price_last_filled_trade: String,
+    #[serde(rename = "L", with = "string_or_float")]
+    pub price_last_filled_trade: NumString,
 
-    #[serde(rename = "n")]
-    pub commission: String,
+    #[serde(rename = "n", with = "string_or_float")]
+    pub commission: NumString,
 
     #[serde(skip, rename = "N")]
     pub asset_commisioned: Option<String>,
@@ -466,11 +510,11 @@ pub struct TradesEvent {
     #[serde(rename = "a")]
     pub aggregated_trade_id: u64,
 
-    #[serde(rename = "p")]
-    pub price: String,
+    #[serde(rename = "p", with = "string_or_float")]
+    pub price: NumString,
 
-    #[serde(rename = "q")]
-    pub qty: String,
+    #[serde(rename = "q", with = "string_or_float")]
+    pub qty: NumString,
 
     #[serde(rename = "f")]
     pub first_break_trade_id: u64,
@@ -497,17 +541,17 @@ pub struct BookTickerEvent {
     #[serde(rename = "s")]
     pub symbol: String,
 
-    #[serde(rename = "b")]
-    pub best_bid: String,
+    #[serde(rename = "b", with = "string_or_float")]
+    pub best_bid: NumString,
 
-    #[serde(rename = "B")]
-    pub best_bid_qty: String,
+    #[serde(rename = "B", with = "string_or_float")]
+    pub best_bid_qty: NumString,
 
-    #[serde(rename = "a")]
-    pub best_ask: String,
+    #[serde(rename = "a", with = "string_or_float")]
+    pub best_ask: NumString,
 
-    #[serde(rename = "A")]
-    pub best_ask_qty: String,
+    #[serde(rename = "A", with = "string_or_float")]
+    pub best_ask_qty: NumString,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -522,50 +566,50 @@ pub struct DayTickerEvent {
     #[serde(rename = "s")]
     pub symbol: String,
 
-    #[serde(rename = "p")]
-    pub price_change: String,
+    #[serde(rename = "p", with = "string_or_float")]
+    pub price_change: NumString,
 
-    #[serde(rename = "P")]
-    pub price_change_percent: String,
+    #[serde(rename = "P", with = "string_or_float")]
+    pub price_change_percent: NumString,
 
-    #[serde(rename = "w")]
-    pub average_price: String,
+    #[serde(rename = "w", with = "string_or_float")]
+    pub average_price: NumString,
 
-    #[serde(rename = "x")]
-    pub prev_close: String,
+    #[serde(rename = "x", default, with = "string_or_float")]
+    pub prev_close: NumString,
 
-    #[serde(rename = "c")]
-    pub current_close: String,
+    #[serde(rename = "c", with = "string_or_float")]
+    pub current_close: NumString,
 
-    #[serde(rename = "Q")]
-    pub current_close_qty: String,
+    #[serde(rename = "Q", with = "string_or_float")]
+    pub current_close_qty: NumString,
 
-    #[serde(rename = "b")]
-    pub best_bid: String,
+    #[serde(rename = "b", default, with = "string_or_float")]
+    pub best_bid: NumString,
 
-    #[serde(rename = "B")]
-    pub best_bid_qty: String,
+    #[serde(rename = "B", default, with = "string_or_float")]
+    pub best_bid_qty: NumString,
 
-    #[serde(rename = "a")]
-    pub best_ask: String,
+    #[serde(rename = "a", default, with = "string_or_float")]
+    pub best_ask: NumString,
 
-    #[serde(rename = "A")]
-    pub best_ask_qty: String,
+    #[serde(rename = "A", default, with = "string_or_float")]
+    pub best_ask_qty: NumString,
 
-    #[serde(rename = "o")]
-    pub open: String,
+    #[serde(rename = "o", with = "string_or_float")]
+    pub open: NumString,
 
-    #[serde(rename = "h")]
-    pub high: String,
+    #[serde(rename = "h", with = "string_or_float")]
+    pub high: NumString,
 
-    #[serde(rename = "l")]
-    pub low: String,
+    #[serde(rename = "l", with = "string_or_float")]
+    pub low: NumString,
 
-    #[serde(rename = "v")]
-    pub volume: String,
+    #[serde(rename = "v", with = "string_or_float")]
+    pub volume: NumString,
 
-    #[serde(rename = "q")]
-    pub quote_volume: String,
+    #[serde(rename = "q", with = "string_or_float")]
+    pub quote_volume: NumString,
 
     #[serde(rename = "O")]
     pub open_time: u64,
@@ -603,25 +647,25 @@ pub struct KlineEvent {
 pub struct KlineSummary {
     pub open_time: i64,
 
-    pub open: f64,
+    pub open: Num,
 
-    pub high: f64,
+    pub high: Num,
 
-    pub low: f64,
+    pub low: Num,
 
-    pub close: f64,
+    pub close: Num,
 
-    pub volume: f64,
+    pub volume: Num,
 
     pub close_time: i64,
 
-    pub quote_asset_volume: f64,
+    pub quote_asset_volume: Num,
 
     pub number_of_trades: i64,
 
-    pub taker_buy_base_asset_volume: f64,
+    pub taker_buy_base_asset_volume: Num,
 
-    pub taker_buy_quote_asset_volume: f64,
+    pub taker_buy_quote_asset_volume: Num,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -637,7 +681,7 @@ pub struct Kline {
     pub symbol: String,
 
     #[serde(rename = "i")]
-    pub interval: String,
+    pub interval: KlineInterval,
 
     #[serde(rename = "f")]
     pub first_trade_id: i32,
@@ -645,20 +689,20 @@ pub struct Kline {
     #[serde(rename = "L")]
     pub last_trade_id: i32,
 
-    #[serde(rename = "o")]
-    pub open: String,
+    #[serde(rename = "o", with = "string_or_float")]
+    pub open: NumString,
 
-    #[serde(rename = "c")]
-    pub close: String,
+    #[serde(rename = "c", with = "string_or_float")]
+    pub close: NumString,
 
-    #[serde(rename = "h")]
-    pub high: String,
+    #[serde(rename = "h", with = "string_or_float")]
+    pub high: NumString,
 
-    #[serde(rename = "l")]
-    pub low: String,
+    #[serde(rename = "l", with = "string_or_float")]
+    pub low: NumString,
 
-    #[serde(rename = "v")]
-    pub volume: String,
+    #[serde(rename = "v", with = "string_or_float")]
+    pub volume: NumString,
 
     #[serde(rename = "n")]
     pub number_of_trades: i32,
@@ -666,14 +710,14 @@ pub struct Kline {
     #[serde(rename = "x")]
     pub is_final_bar: bool,
 
-    #[serde(rename = "q")]
-    pub quote_volume: String,
+    #[serde(rename = "q", with = "string_or_float")]
+    pub quote_volume: NumString,
 
-    #[serde(rename = "V")]
-    pub active_buy_volume: String,
+    #[serde(rename = "V", with = "string_or_float")]
+    pub active_buy_volume: NumString,
 
-    #[serde(rename = "Q")]
-    pub active_volume_buy_quote: String,
+    #[serde(rename = "Q", with = "string_or_float")]
+    pub active_volume_buy_quote: NumString,
 
     #[serde(skip, rename = "B")]
     pub ignore_me: String,
@@ -697,6 +741,10 @@ pub struct DepthOrderBookEvent {
     #[serde(rename = "u")]
     pub final_update_id: u64,
 
+    // Final update id of the previous event, futures only
+    #[serde(rename = "pu", default)]
+    pub previous_final_update_id: Option<u64>,
+
     #[serde(rename = "b")]
     pub bids: Vec<Bids>,
 
@@ -706,6 +754,7 @@ pub struct DepthOrderBookEvent {
 
 pub(crate) mod string_or_float {
     use std::fmt;
+    use std::str::FromStr;
 
     use serde::{de, Serializer, Deserialize, Deserializer};
 
@@ -717,20 +766,67 @@ pub(crate) mod string_or_float {
         serializer.collect_str(value)
     }
 
-    pub fn deserialize<'de, D>(deserializer: D) -> Result<f64, D::Error>
+    // Numbers are parsed from their text, so a `Decimal` keeps the exact value sent
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
     where
+        T: FromStr,
+        T::Err: fmt::Display,
         D: Deserializer<'de>,
     {
-        #[derive(Deserialize)]
-        #[serde(untagged)]
-        enum StringOrFloat {
-            String(String),
-            Float(f64),
+        StringOrFloat::deserialize(deserializer)?.parse()
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    pub(super) enum StringOrFloat {
+        String(String),
+        Float(serde_json::Number),
+    }
+
+    impl StringOrFloat {
+        pub(super) fn parse<T, E>(self) -> Result<T, E>
+        where
+            T: FromStr,
+            T::Err: fmt::Display,
+            E: de::Error,
+        {
+            match self {
+                StringOrFloat::String(s) => s.parse().map_err(de::Error::custom),
+                StringOrFloat::Float(i) => i.to_string().parse().map_err(de::Error::custom),
+            }
+        }
+    }
+}
+
+// `string_or_float` of optional fields, which also need `#[serde(default)]`
+pub(crate) mod option_string_or_float {
+    use std::fmt;
+    use std::str::FromStr;
+
+    use serde::{Serializer, Deserialize, Deserializer};
+
+    use super::string_or_float::StringOrFloat;
+
+    pub fn serialize<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: fmt::Display,
+        S: Serializer,
+    {
+        match *value {
+            Some(ref value) => serializer.collect_str(value),
+            None => serializer.serialize_none(),
         }
+    }
 
-        match StringOrFloat::deserialize(deserializer)? {
-            StringOrFloat::String(s) => s.parse().map_err(de::Error::custom),
-            StringOrFloat::Float(i) => Ok(i),
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+        D: Deserializer<'de>,
+    {
+        match Option::<StringOrFloat>::deserialize(deserializer)? {
+            Some(value) => value.parse().map(Some),
+            None => Ok(None),
         }
     }
 }
@@ -744,8 +840,8 @@ pub struct FuturesAccountUpdateEvent {
     #[serde(rename = "e")]
     pub event_type: String,
 
-    #[serde(skip, rename = "T")]
-    pub t_ignore: u64,
+    #[serde(rename = "T")]
+    pub transaction_time: u64,
 
     #[serde(rename = "E")]
     pub event_time: u64,
@@ -757,14 +853,14 @@ pub struct FuturesAccountUpdateEvent {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct FuturesAccountAssets {
+    #[serde(rename = "m")]
+    pub reason: AccountUpdateReason,
+
     #[serde(rename = "B")]
     pub balances: Vec<FuturesAccountBalance>,
 
     #[serde(rename = "P")]
     pub positions: Vec<FuturesAccountPosition>,
-
-    #[serde(skip, rename = "m")]
-    pub m_ignore: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -773,11 +869,15 @@ pub struct FuturesAccountBalance {
     #[serde(rename = "a")]
     pub asset: String,
 
-    #[serde(rename = "wb")]
-    pub wallet_balance: String,
+    #[serde(rename = "wb", with = "string_or_float")]
+    pub wallet_balance: NumString,
 
-    #[serde(rename = "cw")]
-    pub c_wallet: String,
+    #[serde(rename = "cw", with = "string_or_float")]
+    pub c_wallet: NumString,
+
+    // Balance change except PnL and commission
+    #[serde(rename = "bc", default, with = "option_string_or_float")]
+    pub balance_change: Option<Num>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -786,26 +886,312 @@ pub struct FuturesAccountPosition {
     #[serde(rename = "s")]
     pub symbol: String,
 
-    #[serde(rename = "pa")]
-    pub position_amount: String,
+    #[serde(rename = "pa", with = "string_or_float")]
+    pub position_amount: NumString,
+
+    #[serde(rename = "ep", with = "string_or_float")]
+    pub entry_price: NumString,
+
+    #[serde(rename = "cr", with = "string_or_float")]
+    pub accumulated_realized: NumString,
+
+    #[serde(rename = "up", with = "string_or_float")]
+    pub unrealized_pnl: NumString,
+
+    #[serde(rename = "mt")]
+    pub margin_type: MarginType,
+
+    #[serde(rename = "iw", with = "string_or_float")]
+    pub isolated_wallet: Num,
+
+    #[serde(rename = "ps")]
+    pub position_side: PositionSide,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginCallEvent {
+    #[serde(rename = "e")]
+    pub event_type: String,
+
+    #[serde(rename = "E")]
+    pub event_time: u64,
+
+    // Only sent for crossed positions
+    #[serde(rename = "cw", default, with = "option_string_or_float")]
+    pub cross_wallet_balance: Option<Num>,
+
+    #[serde(rename = "p")]
+    pub positions: Vec<MarginCallPosition>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginCallPosition {
+    #[serde(rename = "s")]
+    pub symbol: String,
+
+    #[serde(rename = "ps")]
+    pub position_side: PositionSide,
+
+    #[serde(rename = "pa", with = "string_or_float")]
+    pub position_amount: Num,
+
+    #[serde(rename = "mt")]
+    pub margin_type: MarginType,
+
+    #[serde(rename = "iw", with = "string_or_float")]
+    pub isolated_wallet: Num,
+
+    #[serde(rename = "mp", with = "string_or_float")]
+    pub mark_price: Num,
+
+    #[serde(rename = "up", with = "string_or_float")]
+    pub unrealized_pnl: Num,
+
+    #[serde(rename = "mm", with = "string_or_float")]
+    pub maintenance_margin_required: Num,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountConfigUpdateEvent {
+    #[serde(rename = "e")]
+    pub event_type: String,
+
+    #[serde(rename = "E")]
+    pub event_time: u64,
+
+    #[serde(rename = "T")]
+    pub transaction_time: u64,
+
+    // Sent when the leverage of a symbol changed
+    #[serde(rename = "ac", default)]
+    pub leverage: Option<LeverageUpdate>,
+
+    // Sent when the multi-assets margin mode changed
+    #[serde(rename = "ai", default)]
+    pub multi_assets: Option<MultiAssetsUpdate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LeverageUpdate {
+    #[serde(rename = "s")]
+    pub symbol: String,
+
+    #[serde(rename = "l")]
+    pub leverage: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiAssetsUpdate {
+    #[serde(rename = "j")]
+    pub multi_assets_margin: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListenKeyExpiredEvent {
+    #[serde(rename = "e")]
+    pub event_type: String,
+
+    #[serde(rename = "E")]
+    pub event_time: u64,
+
+    #[serde(rename = "listenKey", default)]
+    pub listen_key: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LiquidationEvent {
+    #[serde(rename = "e")]
+    pub event_type: String,
+
+    #[serde(rename = "E")]
+    pub event_time: u64,
+
+    #[serde(rename = "o")]
+    pub liquidation_order: LiquidationOrderEvent,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LiquidationOrderEvent {
+    #[serde(rename = "s")]
+    pub symbol: String,
+
+    #[serde(rename = "S")]
+    pub side: OrderSide,
+
+    #[serde(rename = "o")]
+    pub order_type: OrderType,
+
+    #[serde(rename = "f")]
+    pub time_in_force: TimeInForce,
+
+    #[serde(rename = "q", with = "string_or_float")]
+    pub original_qty: Num,
+
+    #[serde(rename = "p", with = "string_or_float")]
+    pub price: Num,
+
+    #[serde(rename = "ap", with = "string_or_float")]
+    pub avg_price: Num,
+
+    #[serde(rename = "X")]
+    pub order_status: OrderStatus,
+
+    #[serde(rename = "l", with = "string_or_float")]
+    pub qty_last_filled_trade: Num,
+
+    #[serde(rename = "z", with = "string_or_float")]
+    pub accumulated_qty_filled_trades: Num,
+
+    #[serde(rename = "T")]
+    pub trade_time: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MiniTickerEvent {
+    #[serde(rename = "e")]
+    pub event_type: String,
+
+    #[serde(rename = "E")]
+    pub event_time: u64,
+
+    #[serde(rename = "s")]
+    pub symbol: String,
+
+    #[serde(rename = "c", with = "string_or_float")]
+    pub close: Num,
+
+    #[serde(rename = "o", with = "string_or_float")]
+    pub open: Num,
+
+    #[serde(rename = "h", with = "string_or_float")]
+    pub high: Num,
+
+    #[serde(rename = "l", with = "string_or_float")]
+    pub low: Num,
+
+    #[serde(rename = "v", with = "string_or_float")]
+    pub volume: Num,
+
+    #[serde(rename = "q", with = "string_or_float")]
+    pub quote_volume: Num,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ContinuousKlineEvent {
+    #[serde(rename = "e")]
+    pub event_type: String,
+
+    #[serde(rename = "E")]
+    pub event_time: u64,
+
+    #[serde(rename = "ps")]
+    pub pair: String,
+
+    #[serde(rename = "ct")]
+    pub contract_type: ContractType,
+
+    #[serde(rename = "k")]
+    pub kline: ContinuousKline,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ContinuousKline {
+    #[serde(rename = "t")]
+    pub start_time: i64,
+
+    #[serde(rename = "T")]
+    pub end_time: i64,
+
+    #[serde(rename = "i")]
+    pub interval: KlineInterval,
+
+    #[serde(rename = "f")]
+    pub first_update_id: i64,
+
+    #[serde(rename = "L")]
+    pub last_update_id: i64,
+
+    #[serde(rename = "o", with = "string_or_float")]
+    pub open: Num,
+
+    #[serde(rename = "c", with = "string_or_float")]
+    pub close: Num,
+
+    #[serde(rename = "h", with = "string_or_float")]
+    pub high: Num,
+
+    #[serde(rename = "l", with = "string_or_float")]
+    pub low: Num,
+
+    #[serde(rename = "v", with = "string_or_float")]
+    pub volume: Num,
+
+    #[serde(rename = "n")]
+    pub number_of_trades: i64,
+
+    #[serde(rename = "x")]
+    pub is_final_bar: bool,
+
+    #[serde(rename = "q", with = "string_or_float")]
+    pub quote_volume: Num,
+
+    #[serde(rename = "V", with = "string_or_float")]
+    pub active_buy_volume: Num,
+
+    #[serde(rename = "Q", with = "string_or_float")]
+    pub active_volume_buy_quote: Num,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CompositeIndexEvent {
+    #[serde(rename = "e")]
+    pub event_type: String,
+
+    #[serde(rename = "E")]
+    pub event_time: u64,
+
+    #[serde(rename = "s")]
+    pub symbol: String,
+
+    #[serde(rename = "p", with = "string_or_float")]
+    pub price: Num,
+
+    #[serde(rename = "C", default)]
+    pub composition_type: Option<String>,
 
-    #[serde(rename = "ep")]
-    pub entry_price: String,
+    #[serde(rename = "c")]
+    pub composition: Vec<IndexComposition>,
+}
 
-    #[serde(rename = "cr")]
-    pub accumulated_realized: String,
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexComposition {
+    #[serde(rename = "b")]
+    pub base_asset: String,
 
-    #[serde(rename = "up")]
-    pub unrealized_pnl: String,
+    #[serde(rename = "q")]
+    pub quote_asset: String,
 
-    #[serde(skip, rename = "mt")]
-    pub mt_ignore: String,
+    #[serde(rename = "w", with = "string_or_float")]
+    pub weight_in_qty: Num,
 
-    #[serde(skip, rename = "iw")]
-    pub iw_ignore: String,
+    #[serde(rename = "W", with = "string_or_float")]
+    pub weight_in_percentage: Num,
 
-    #[serde(skip, rename = "ps")]
-    pub ps_ignore: String,
+    #[serde(rename = "i", default, with = "option_string_or_float")]
+    pub index_price: Option<Num>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -831,43 +1217,43 @@ pub struct OrderTradeUpdateOrder {
     pub new_client_order_id: String,
 
     #[serde(rename = "S")]
-    pub side: String,
+    pub side: OrderSide,
 
     #[serde(rename = "o")]
-    pub order_type: String,
+    pub order_type: OrderType,
 
     #[serde(rename = "f")]
-    pub time_in_force: String,
+    pub time_in_force: TimeInForce,
 
-    #[serde(rename = "q")]
-    pub qty: String,
+    #[serde(rename = "q", with = "string_or_float")]
+    pub qty: NumString,
 
-    #[serde(rename = "p")]
-    pub price: String,
+    #[serde(rename = "p", with = "string_or_float")]
+    pub price: NumString,
 
-    #[serde(rename = "ap")]
-    pub avg_price: String,
+    #[serde(rename = "ap", with = "string_or_float")]
+    pub avg_price: NumString,
 
-    #[serde(rename = "sp")]
-    pub stop_price: String,
+    #[serde(rename = "sp", with = "string_or_float")]
+    pub stop_price: NumString,
 
     #[serde(rename = "x")]
-    pub execution_type: String,
+    pub execution_type: ExecutionType,
 
     #[serde(rename = "X")]
-    pub order_status: String,
+    pub order_status: OrderStatus,
 
     #[serde(rename = "i")]
     pub order_id: u64,
 
-    #[serde(rename = "l")]
-    pub qty_last_filled_trade: String,
+    #[serde(rename = "l", with = "string_or_float")]
+    pub qty_last_filled_trade: NumString,
 
-    #[serde(rename = "z")]
-    pub accumulated_qty_filled_trades: String,
+    #[serde(rename = "z", with = "string_or_float")]
+    pub accumulated_qty_filled_trades: NumString,
 
-    #[serde(rename = "L")]
-    pub price_last_filled_trade: String,
+    #[serde(rename = "L", with = "string_or_float")]
+    pub price_last_filled_trade: NumString,
 
     #[serde(skip, rename = "n")]
     pub n_ignore: String,
@@ -896,32 +1282,32 @@ pub struct OrderTradeUpdateOrder {
 pub struct FuturesTransaction {
     pub order_id: u64,
     pub symbol: String,
-    pub status: String,
+    pub status: OrderStatus,
     pub client_order_id: String,
     #[serde(with = "string_or_float")]
-    pub price: f64,
+    pub price: Num,
     #[serde(with = "string_or_float")]
-    pub avg_price: f64,
+    pub avg_price: Num,
     #[serde(with = "string_or_float")]
-    pub orig_qty: f64,
+    pub orig_qty: Num,
     #[serde(with = "string_or_float")]
-    pub executed_qty: f64,
+    pub executed_qty: Num,
     #[serde(with = "string_or_float")]
-    pub cum_qty: f64,
+    pub cum_qty: Num,
     #[serde(with = "string_or_float")]
-    pub cum_quote: f64,
-    pub time_in_force: String,
+    pub cum_quote: Num,
+    pub time_in_force: TimeInForce,
     #[serde(rename = "type")]
-    pub type_name: String,
+    pub type_name: OrderType,
     pub reduce_only: bool,
     pub close_position: bool,
-    pub side: String,
-    pub position_side: String,
+    pub side: OrderSide,
+    pub position_side: PositionSide,
     #[serde(with = "string_or_float")]
-    pub stop_price: f64,
-    pub working_type: String,
+    pub stop_price: Num,
+    pub working_type: WorkingType,
     pub price_protect: bool,
-    pub orig_type: String,
+    pub orig_type: OrderType,
     pub update_time: u64,
 }
 
@@ -930,37 +1316,37 @@ pub struct FuturesTransaction {
 pub struct FuturesOrder {
     pub order_id: u64,
     pub symbol: String,
-    pub status: String,
+    pub status: OrderStatus,
     pub client_order_id: String,
     #[serde(with = "string_or_float")]
-    pub price: f64,
+    pub price: Num,
     #[serde(with = "string_or_float")]
-    pub avg_price: f64,
+    pub avg_price: Num,
     #[serde(with = "string_or_float")]
-    pub orig_qty: f64,
+    pub orig_qty: Num,
     #[serde(with = "string_or_float")]
-    pub executed_qty: f64,
+    pub executed_qty: Num,
     #[serde(skip)]
-    pub cum_qty: f64,
+    pub cum_qty: Num,
     #[serde(with = "string_or_float")]
-    pub cum_quote: f64,
-    pub time_in_force: String,
+    pub cum_quote: Num,
+    pub time_in_force: TimeInForce,
     #[serde(rename = "type")]
-    pub type_name: String,
+    pub type_name: OrderType,
     pub reduce_only: bool,
     pub close_position: bool,
-    pub side: String,
-    pub position_side: String,
+    pub side: OrderSide,
+    pub position_side: PositionSide,
     #[serde(with = "string_or_float")]
-    pub stop_price: f64,
-    pub working_type: String,
-    pub orig_type: String,
+    pub stop_price: Num,
+    pub working_type: WorkingType,
+    pub orig_type: OrderType,
     pub time: u64,
     pub update_time: u64,
     #[serde(skip)]
-    pub activate_price: String,
+    pub activate_price: NumString,
     #[serde(skip)]
-    pub price_rate: String,
+    pub price_rate: NumString,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -978,17 +1364,28 @@ pub struct AccountInformationV2 {
     pub can_deposit: bool,
     pub can_withdraw: bool,
     pub update_time: u64,
-    pub total_initial_margin: String,
-    pub total_maint_margin: String,
-    pub total_wallet_balance: String,
-    pub total_unrealized_profit: String,
-    pub total_margin_balance: String,
-    pub total_position_initial_margin: String,
-    pub total_open_order_initial_margin: String,
-    pub total_cross_wallet_balance: String,
-    pub total_cross_un_pnl: String,
-    pub available_balance: String,
-    pub max_withdraw_amount: String,
+    #[serde(with = "string_or_float")]
+    pub total_initial_margin: NumString,
+    #[serde(with = "string_or_float")]
+    pub total_maint_margin: NumString,
+    #[serde(with = "string_or_float")]
+    pub total_wallet_balance: NumString,
+    #[serde(with = "string_or_float")]
+    pub total_unrealized_profit: NumString,
+    #[serde(with = "string_or_float")]
+    pub total_margin_balance: NumString,
+    #[serde(with = "string_or_float")]
+    pub total_position_initial_margin: NumString,
+    #[serde(with = "string_or_float")]
+    pub total_open_order_initial_margin: NumString,
+    #[serde(with = "string_or_float")]
+    pub total_cross_wallet_balance: NumString,
+    #[serde(with = "string_or_float")]
+    pub total_cross_un_pnl: NumString,
+    #[serde(with = "string_or_float")]
+    pub available_balance: NumString,
+    #[serde(with = "string_or_float")]
+    pub max_withdraw_amount: NumString,
     pub assets: Vec<AssetV2>,
     pub positions: Vec<PositionV2>,
 }
@@ -997,50 +1394,97 @@ pub struct AccountInformationV2 {
 #[serde(rename_all = "camelCase")]
 pub struct AssetV2 {
     pub asset: String,
-    pub wallet_balance: String,
-    pub unrealized_profit: String,
-    pub margin_balance: String,
-    pub maint_margin: String,
-    pub initial_margin: String,
-    pub position_initial_margin: String,
-    pub open_order_initial_margin: String,
-    pub cross_wallet_balance: String,
-    pub cross_un_pnl: String,
-    pub available_balance: String,
-    pub max_withdraw_amount: String,
+    #[serde(with = "string_or_float")]
+    pub wallet_balance: NumString,
+    #[serde(with = "string_or_float")]
+    pub unrealized_profit: NumString,
+    #[serde(with = "string_or_float")]
+    pub margin_balance: NumString,
+    #[serde(with = "string_or_float")]
+    pub maint_margin: NumString,
+    #[serde(with = "string_or_float")]
+    pub initial_margin: NumString,
+    #[serde(with = "string_or_float")]
+    pub position_initial_margin: NumString,
+    #[serde(with = "string_or_float")]
+    pub open_order_initial_margin: NumString,
+    #[serde(with = "string_or_float")]
+    pub cross_wallet_balance: NumString,
+    #[serde(with = "string_or_float")]
+    pub cross_un_pnl: NumString,
+    #[serde(with = "string_or_float")]
+    pub available_balance: NumString,
+    #[serde(with = "string_or_float")]
+    pub max_withdraw_amount: NumString,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct PositionV2 {
     pub symbol: String,
-    pub initial_margin: String,
-    pub maint_margin: String,
-    pub unrealized_profit: String,
-    pub position_initial_margin: String,
-    pub open_order_initial_margin: String,
-    pub leverage: String,
+    #[serde(with = "string_or_float")]
+    pub initial_margin: NumString,
+    #[serde(with = "string_or_float")]
+    pub maint_margin: NumString,
+    #[serde(with = "string_or_float")]
+    pub unrealized_profit: NumString,
+    #[serde(with = "string_or_float")]
+    pub position_initial_margin: NumString,
+    #[serde(with = "string_or_float")]
+    pub open_order_initial_margin: NumString,
+    #[serde(with = "string_or_float")]
+    pub leverage: NumString,
     pub isolated: bool,
-    pub entry_price: String,
-    pub max_notional: String,
-    pub position_side: String,
+    #[serde(with = "string_or_float")]
+    pub entry_price: NumString,
+    #[serde(with = "string_or_float")]
+    pub max_notional: NumString,
+    pub position_side: PositionSide,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct FuturesPositionV2 {
-    pub entry_price: String,
+    #[serde(with = "string_or_float")]
+    pub entry_price: NumString,
     pub margin_type: String,
     pub is_auto_add_margin: String,
-    pub isolated_margin: String,
-    pub leverage: String,
-    pub liquidation_price: String,
-    pub mark_price: String,
-    pub max_notional_value: String,
-    pub position_amt: String,
+    #[serde(with = "string_or_float")]
+    pub isolated_margin: NumString,
+    #[serde(with = "string_or_float")]
+    pub leverage: NumString,
+    #[serde(with = "string_or_float")]
+    pub liquidation_price: NumString,
+    #[serde(with = "string_or_float")]
+    pub mark_price: NumString,
+    #[serde(with = "string_or_float")]
+    pub max_notional_value: NumString,
+    #[serde(with = "string_or_float")]
+    pub position_amt: NumString,
     pub symbol: String,
-    pub un_realized_profit: String,
-    pub position_side: String,
+    #[serde(with = "string_or_float")]
+    pub un_realized_profit: NumString,
+    pub position_side: PositionSide,
+}
+
+/// Balance of one asset of the futures account, as in `/fapi/v2/balance`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FuturesAssetBalance {
+    pub account_alias: String,
+    pub asset: String,
+    #[serde(with = "string_or_float")]
+    pub balance: Num,
+    #[serde(with = "string_or_float")]
+    pub cross_wallet_balance: Num,
+    #[serde(rename = "crossUnPnl", with = "string_or_float")]
+    pub cross_unrealized_pnl: Num,
+    #[serde(with = "string_or_float")]
+    pub available_balance: Num,
+    #[serde(with = "string_or_float")]
+    pub max_withdraw_amount: Num,
+    pub margin_available: bool,
+    pub update_time: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -1049,7 +1493,7 @@ pub struct FuturesIncome {
     pub symbol: String,
     pub income_type: String,
     #[serde(with = "string_or_float")]
-    pub income: f64,
+    pub income: Num,
     pub asset: String,
     pub info: String,
     pub time: u64,
@@ -1069,15 +1513,498 @@ pub struct FuturesFunding {
     #[serde(rename = "s")]
     pub symbol: String,
 
-    #[serde(rename = "p")]
-    pub mark_price: String,
+    #[serde(rename = "p", with = "string_or_float")]
+    pub mark_price: NumString,
 
-    #[serde(rename = "i")]
-    pub index_price: String,
+    #[serde(rename = "i", with = "string_or_float")]
+    pub index_price: NumString,
 
-    #[serde(rename = "r")]
-    pub funding_rate: String,
+    #[serde(rename = "r", with = "string_or_float")]
+    pub funding_rate: NumString,
 
     #[serde(rename = "T")]
     pub next_funding_time: u64,
 }
+
+// Enums of the string values sent by the exchange. Values added by Binance after
+// this release are kept as `Unknown(value)`, so they don't break the parsing.
+// Other spellings of a value can follow the first one, which is the one sent back.
+macro_rules! string_enum {
+    ($(#[$meta:meta])* $name:ident {
+        $($(#[$variant_meta:meta])* $variant:ident => $value:literal $(| $alias:literal)*,)*
+    }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+            Unknown(String),
+        }
+
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Unknown(value) => value.as_str(),
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                match value {
+                    $($value $(| $alias)* => $name::$variant,)*
+                    value => $name::Unknown(value.to_string()),
+                }
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                $name::from(value.as_str())
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                Ok($name::from(String::deserialize(deserializer)?))
+            }
+        }
+    };
+}
+
+string_enum! {
+    OrderSide {
+        Buy => "BUY",
+        Sell => "SELL",
+    }
+}
+
+string_enum! {
+    OrderType {
+        Limit => "LIMIT",
+        Market => "MARKET",
+        Stop => "STOP",
+        StopMarket => "STOP_MARKET",
+        TakeProfit => "TAKE_PROFIT",
+        TakeProfitMarket => "TAKE_PROFIT_MARKET",
+        TrailingStopMarket => "TRAILING_STOP_MARKET",
+        Liquidation => "LIQUIDATION",
+        // Spot only
+        StopLoss => "STOP_LOSS",
+        StopLossLimit => "STOP_LOSS_LIMIT",
+        TakeProfitLimit => "TAKE_PROFIT_LIMIT",
+        LimitMaker => "LIMIT_MAKER",
+    }
+}
+
+string_enum! {
+    OrderStatus {
+        New => "NEW",
+        PartiallyFilled => "PARTIALLY_FILLED",
+        Filled => "FILLED",
+        Canceled => "CANCELED",
+        PendingCancel => "PENDING_CANCEL",
+        Rejected => "REJECTED",
+        Expired => "EXPIRED",
+        NewInsurance => "NEW_INSURANCE",
+        NewAdl => "NEW_ADL",
+    }
+}
+
+string_enum! {
+    TimeInForce {
+        Gtc => "GTC",
+        Ioc => "IOC",
+        Fok => "FOK",
+        /// Post only
+        Gtx => "GTX",
+        Gtd => "GTD",
+    }
+}
+
+string_enum! {
+    PositionSide {
+        Both => "BOTH",
+        Long => "LONG",
+        Short => "SHORT",
+    }
+}
+
+string_enum! {
+    /// Margin type of a futures position. The user data stream sends `isolated`
+    /// and `cross` in the account updates.
+    MarginType {
+        Isolated => "ISOLATED" | "isolated",
+        Crossed => "CROSSED" | "cross",
+    }
+}
+
+string_enum! {
+    /// Price compared with the stop price of conditional orders.
+    WorkingType {
+        MarkPrice => "MARK_PRICE",
+        ContractPrice => "CONTRACT_PRICE",
+    }
+}
+
+string_enum! {
+    /// Execution type of the order update events.
+    ExecutionType {
+        New => "NEW",
+        Canceled => "CANCELED",
+        Calculated => "CALCULATED",
+        Expired => "EXPIRED",
+        Trade => "TRADE",
+        Amendment => "AMENDMENT",
+        Replaced => "REPLACED",
+        Rejected => "REJECTED",
+    }
+}
+
+string_enum! {
+    KlineInterval {
+        Minutes1 => "1m",
+        Minutes3 => "3m",
+        Minutes5 => "5m",
+        Minutes15 => "15m",
+        Minutes30 => "30m",
+        Hours1 => "1h",
+        Hours2 => "2h",
+        Hours4 => "4h",
+        Hours6 => "6h",
+        Hours8 => "8h",
+        Hours12 => "12h",
+        Days1 => "1d",
+        Days3 => "3d",
+        Weeks1 => "1w",
+        Months1 => "1M",
+    }
+}
+
+string_enum! {
+    /// Contract of the continuous kline streams.
+    ContractType {
+        Perpetual => "PERPETUAL",
+        CurrentQuarter => "CURRENT_QUARTER",
+        NextQuarter => "NEXT_QUARTER",
+    }
+}
+
+string_enum! {
+    /// Reason of the futures `ACCOUNT_UPDATE` events.
+    AccountUpdateReason {
+        Deposit => "DEPOSIT",
+        Withdraw => "WITHDRAW",
+        Order => "ORDER",
+        FundingFee => "FUNDING_FEE",
+        WithdrawReject => "WITHDRAW_REJECT",
+        Adjustment => "ADJUSTMENT",
+        InsuranceClear => "INSURANCE_CLEAR",
+        AdminDeposit => "ADMIN_DEPOSIT",
+        AdminWithdraw => "ADMIN_WITHDRAW",
+        MarginTransfer => "MARGIN_TRANSFER",
+        MarginTypeChange => "MARGIN_TYPE_CHANGE",
+        AssetTransfer => "ASSET_TRANSFER",
+        OptionsPremiumFee => "OPTIONS_PREMIUM_FEE",
+        OptionsSettleProfit => "OPTIONS_SETTLE_PROFIT",
+        AutoExchange => "AUTO_EXCHANGE",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::DeserializeOwned;
+    use serde_json::Value;
+
+    // Deserializes the payload and checks it serializes back to the same JSON
+    fn round_trip<T: DeserializeOwned + Serialize>(json: &str) -> T {
+        let value: T = serde_json::from_str(json).unwrap();
+        let expected: Value = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::to_value(&value).unwrap(), expected);
+
+        value
+    }
+
+    const BALANCE: &str = r#"{"asset": "BTC", "free": "4723846.89208129", "locked": "0.00000000"}"#;
+
+    const ORDER: &str = r#"{
+        "symbol": "LTCBTC", "orderId": 1, "clientOrderId": "myOrder1", "price": "0.1",
+        "origQty": "1.00000000", "executedQty": "0.00000000", "status": "NEW",
+        "timeInForce": "GTC", "type": "LIMIT", "side": "BUY", "stopPrice": "0",
+        "icebergQty": "0.00000000", "time": 1499827319559
+    }"#;
+
+    const FILTERS: &str = r#"[
+        {"filterType": "PRICE_FILTER", "minPrice": "556.80", "maxPrice": "4529764", "tickSize": "0.10"},
+        {"filterType": "LOT_SIZE", "minQty": "0.001", "maxQty": "1000", "stepSize": "0.001"},
+        {"filterType": "MIN_NOTIONAL", "minNotional": "5.0", "applyToMarket": true, "avgPriceMins": 5.0}
+    ]"#;
+
+    const ACCOUNT_V2: &str = r#"{
+        "feeTier": 0, "canTrade": true, "canDeposit": true, "canWithdraw": true, "updateTime": 0,
+        "totalInitialMargin": "0.00000000", "totalMaintMargin": "0.00000000",
+        "totalWalletBalance": "23.72469206", "totalUnrealizedProfit": "0.00000000",
+        "totalMarginBalance": "23.72469206", "totalPositionInitialMargin": "0.00000000",
+        "totalOpenOrderInitialMargin": "0.00000000", "totalCrossWalletBalance": "23.72469206",
+        "totalCrossUnPnl": "0.00000000", "availableBalance": "23.72469206",
+        "maxWithdrawAmount": "23.72469206", "assets": [], "positions": []
+    }"#;
+
+    #[cfg(not(feature = "decimal"))]
+    #[test]
+    fn string_amounts_stay_strings() {
+        let balance: Balance = round_trip(BALANCE);
+        let free: &String = &balance.free;
+        assert_eq!(free, "4723846.89208129");
+        assert_eq!(balance.locked, "0.00000000");
+
+        let order: Order = round_trip(ORDER);
+        assert_eq!(order.orig_qty, "1.00000000");
+        assert_eq!(order.iceberg_qty, "0.00000000");
+        assert_eq!(order.price, 0.1);
+
+        let account: AccountInformationV2 = round_trip(ACCOUNT_V2);
+        assert_eq!(account.total_wallet_balance, "23.72469206");
+    }
+
+    #[cfg(not(feature = "decimal"))]
+    #[test]
+    fn filters_stay_strings() {
+        let filters: Vec<Filters> = serde_json::from_str(FILTERS).unwrap();
+        match filters[0] {
+            Filters::PriceFilter { ref tick_size, .. } => assert_eq!(tick_size, "0.10"),
+            _ => panic!("expected PRICE_FILTER"),
+        }
+        match filters[2] {
+            Filters::MinNotional { ref min_notional, .. } => assert_eq!(min_notional, "5.0"),
+            _ => panic!("expected MIN_NOTIONAL"),
+        }
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn string_amounts_are_exact_decimals() {
+        use std::str::FromStr;
+
+        let balance: Balance = round_trip(BALANCE);
+        assert_eq!(balance.free, Num::from_str("4723846.89208129").unwrap());
+        assert_eq!(balance.locked, Num::from_str("0").unwrap());
+
+        let order: Order = round_trip(ORDER);
+        assert_eq!(order.orig_qty, Num::from_str("1").unwrap());
+        assert_eq!(order.price, Num::from_str("0.1").unwrap());
+
+        let account: AccountInformationV2 = round_trip(ACCOUNT_V2);
+        assert_eq!(account.total_wallet_balance, Num::from_str("23.72469206").unwrap());
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn filters_are_exact_decimals() {
+        use std::str::FromStr;
+
+        let filters: Vec<Filters> = serde_json::from_str(FILTERS).unwrap();
+        match filters[0] {
+            Filters::PriceFilter { tick_size, .. } => {
+                assert_eq!(tick_size, Num::from_str("0.1").unwrap());
+                // The scale is kept, so it serializes back as sent
+                assert_eq!(tick_size.to_string(), "0.10");
+            }
+            _ => panic!("expected PRICE_FILTER"),
+        }
+    }
+
+    #[test]
+    fn numbers_accepted_as_well_as_strings() {
+        let balance: Balance =
+            serde_json::from_str(r#"{"asset": "BTC", "free": 1.5, "locked": 0}"#).unwrap();
+        assert_eq!(balance.free.to_string(), "1.5");
+        assert_eq!(balance.locked.to_string(), "0");
+    }
+
+    fn num(value: &str) -> Num {
+        value.parse().unwrap()
+    }
+
+    // Payloads of the futures user data and market streams documentation
+
+    #[test]
+    fn account_update_event() {
+        let event: FuturesAccountUpdateEvent = serde_json::from_str(
+            r#"{
+                "e": "ACCOUNT_UPDATE", "E": 1564745798939, "T": 1564745798938,
+                "a": {
+                    "m": "ORDER",
+                    "B": [
+                        {"a": "USDT", "wb": "122624.12345678", "cw": "100.12345678", "bc": "50.12345678"},
+                        {"a": "BUSD", "wb": "1.00000000", "cw": "0.00000000"}
+                    ],
+                    "P": [
+                        {"s": "BTCUSDT", "pa": "0", "ep": "0.00000", "cr": "200", "up": "0",
+                         "mt": "isolated", "iw": "0.00000000", "ps": "BOTH"}
+                    ]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let balance = &event.assets.balances[0];
+        assert_eq!(balance.wallet_balance.to_string(), "122624.12345678");
+        assert_eq!(balance.c_wallet.to_string(), "100.12345678");
+        assert_eq!(balance.balance_change, Some(num("50.12345678")));
+        assert_eq!(event.assets.balances[1].balance_change, None);
+        let position = &event.assets.positions[0];
+        assert_eq!(position.entry_price.to_string(), "0.00000");
+        assert_eq!(position.accumulated_realized.to_string(), "200");
+        assert_eq!(position.margin_type, MarginType::Isolated);
+        assert_eq!(position.isolated_wallet, num("0"));
+        assert_eq!(position.position_side, PositionSide::Both);
+    }
+
+    #[test]
+    fn margin_call_event() {
+        let event: MarginCallEvent = serde_json::from_str(
+            r#"{
+                "e": "MARGIN_CALL", "E": 1587727187525, "cw": "3.16812045",
+                "p": [
+                    {"s": "ETHUSDT", "ps": "LONG", "pa": "1.327", "mt": "CROSSED", "iw": "0",
+                     "mp": "187.17127", "up": "-1.166074", "mm": "1.614445"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(event.cross_wallet_balance, Some(num("3.16812045")));
+        let position = &event.positions[0];
+        assert_eq!(position.position_amount, num("1.327"));
+        assert_eq!(position.margin_type, MarginType::Crossed);
+        assert_eq!(position.mark_price, num("187.17127"));
+        assert_eq!(position.unrealized_pnl, num("-1.166074"));
+        assert_eq!(position.maintenance_margin_required, num("1.614445"));
+    }
+
+    #[test]
+    fn liquidation_event() {
+        let event: LiquidationEvent = serde_json::from_str(
+            r#"{
+                "e": "forceOrder", "E": 1568014460893,
+                "o": {"s": "BTCUSDT", "S": "SELL", "o": "LIMIT", "f": "IOC", "q": "0.014",
+                      "p": "9910", "ap": "9910", "X": "FILLED", "l": "0.014", "z": "0.014",
+                      "T": 1568014460893}
+            }"#,
+        )
+        .unwrap();
+
+        let order = &event.liquidation_order;
+        assert_eq!(order.side, OrderSide::Sell);
+        assert_eq!(order.original_qty, num("0.014"));
+        assert_eq!(order.price, num("9910"));
+        assert_eq!(order.avg_price, num("9910"));
+        assert_eq!(order.accumulated_qty_filled_trades, num("0.014"));
+    }
+
+    #[test]
+    fn mini_ticker_event() {
+        let event: MiniTickerEvent = serde_json::from_str(
+            r#"{"e": "24hrMiniTicker", "E": 123456789, "s": "BTCUSDT", "c": "0.0025",
+                "o": "0.0010", "h": "0.0025", "l": "0.0010", "v": "10000", "q": "18"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(event.close, num("0.0025"));
+        assert_eq!(event.open, num("0.0010"));
+        assert_eq!(event.volume, num("10000"));
+        assert_eq!(event.quote_volume, num("18"));
+    }
+
+    #[test]
+    fn continuous_kline_event() {
+        let event: ContinuousKlineEvent = serde_json::from_str(
+            r#"{
+                "e": "continuous_kline", "E": 1607443058651, "ps": "BTCUSDT", "ct": "PERPETUAL",
+                "k": {"t": 1607443020000, "T": 1607443079999, "i": "1m", "f": 116467658886,
+                      "L": 116468012423, "o": "18787.00", "c": "18804.04", "h": "18804.04",
+                      "l": "18786.54", "v": "197.664", "n": 543, "x": false, "q": "3715253.19494",
+                      "V": "184.769", "Q": "3472925.84746", "B": "0"}
+            }"#,
+        )
+        .unwrap();
+
+        let kline = &event.kline;
+        assert_eq!(kline.open, num("18787.00"));
+        assert_eq!(kline.low, num("18786.54"));
+        assert_eq!(kline.quote_volume, num("3715253.19494"));
+        assert_eq!(kline.active_buy_volume, num("184.769"));
+        assert_eq!(kline.active_volume_buy_quote, num("3472925.84746"));
+    }
+
+    #[test]
+    fn composite_index_event() {
+        let event: CompositeIndexEvent = serde_json::from_str(
+            r#"{
+                "e": "compositeIndex", "E": 1602310596000, "s": "DEFIUSDT", "p": "554.41604065",
+                "C": "baseAsset",
+                "c": [
+                    {"b": "BAL", "q": "USDT", "w": "1.04884844", "W": "0.01457800", "i": "24.33521021"},
+                    {"b": "BAND", "q": "USDT", "w": "3.53782729", "W": "0.03935200"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(event.price, num("554.41604065"));
+        assert_eq!(event.composition[0].weight_in_qty, num("1.04884844"));
+        assert_eq!(event.composition[0].weight_in_percentage, num("0.014578"));
+        assert_eq!(event.composition[0].index_price, Some(num("24.33521021")));
+        assert_eq!(event.composition[1].index_price, None);
+    }
+
+    #[test]
+    fn book_ticker_event() {
+        let event: BookTickerEvent = round_trip(
+            r#"{"u": 400900217, "s": "BNBUSDT", "b": "25.35190000", "B": "31.21000000",
+                "a": "25.36520000", "A": "40.66000000"}"#,
+        );
+        assert_eq!(event.best_bid.to_string(), "25.35190000");
+        assert_eq!(event.best_ask_qty.to_string(), "40.66000000");
+    }
+
+    #[test]
+    fn mark_price_event() {
+        let event: FuturesFunding = round_trip(
+            r#"{"e": "markPriceUpdate", "E": 1562305380000, "s": "BTCUSDT",
+                "p": "11794.15000000", "i": "11784.62659091", "r": "0.00038167", "T": 1562306400000}"#,
+        );
+        assert_eq!(event.mark_price.to_string(), "11794.15000000");
+        assert_eq!(event.index_price.to_string(), "11784.62659091");
+        assert_eq!(event.funding_rate.to_string(), "0.00038167");
+    }
+
+    #[test]
+    fn optional_amounts_serialized() {
+        let balance: FuturesAccountBalance =
+            serde_json::from_str(r#"{"a": "USDT", "wb": "1.5", "cw": "1.5", "bc": 2.5}"#).unwrap();
+        let value = serde_json::to_value(&balance).unwrap();
+        assert_eq!(value["bc"], "2.5");
+
+        let balance = FuturesAccountBalance {
+            balance_change: None,
+            ..balance
+        };
+        let value = serde_json::to_value(&balance).unwrap();
+        assert_eq!(value["bc"], Value::Null);
+    }
+}
@@ -0,0 +1,566 @@
+use crate::errors::*;
+use crate::futures::market::FuturesMarket;
+use crate::futures::model as futures;
+use crate::market::Market;
+use crate::model::{self as spot, Asks, Bids, DepthOrderBookEvent, Num, OrderSide};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, VecDeque};
+
+/// REST depth snapshot the local order book starts from, spot or futures.
+pub trait DepthSnapshot {
+    fn last_update_id(&self) -> u64;
+    fn bids(&self) -> &[Bids];
+    fn asks(&self) -> &[Asks];
+}
+
+impl DepthSnapshot for spot::OrderBook {
+    fn last_update_id(&self) -> u64 {
+        self.last_update_id
+    }
+
+    fn bids(&self) -> &[Bids] {
+        &self.bids
+    }
+
+    fn asks(&self) -> &[Asks] {
+        &self.asks
+    }
+}
+
+impl DepthSnapshot for futures::OrderBook {
+    fn last_update_id(&self) -> u64 {
+        self.last_update_id
+    }
+
+    fn bids(&self) -> &[Bids] {
+        &self.bids
+    }
+
+    fn asks(&self) -> &[Asks] {
+        &self.asks
+    }
+}
+
+/// Client downloading the depth snapshots of `LocalOrderBook::sync`, spot `Market`
+/// or `FuturesMarket`.
+pub trait DepthSnapshotSource {
+    type Snapshot: DepthSnapshot;
+
+    fn depth_snapshot(&self, symbol: &str) -> Result<Self::Snapshot>;
+}
+
+impl DepthSnapshotSource for Market {
+    type Snapshot = spot::OrderBook;
+
+    fn depth_snapshot(&self, symbol: &str) -> Result<spot::OrderBook> {
+        self.get_depth(symbol)
+    }
+}
+
+impl DepthSnapshotSource for FuturesMarket {
+    type Snapshot = futures::OrderBook;
+
+    fn depth_snapshot(&self, symbol: &str) -> Result<futures::OrderBook> {
+        self.get_depth(symbol)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceLevel {
+    pub price: Num,
+    pub qty: Num,
+}
+
+/// What `LocalOrderBook::update` did with a diff depth event.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DepthUpdate {
+    /// Kept until a snapshot is applied.
+    Buffered,
+    Applied,
+    /// Already contained in the snapshot or a previous event, ignored.
+    Stale,
+    /// Updates were missed between `last_update_id` and the event. The book was
+    /// cleared and buffers the events again until a new snapshot is applied.
+    Gap {
+        last_update_id: u64,
+        first_update_id: u64,
+    },
+}
+
+// Total order on prices, which are never NaN
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Price(Num);
+
+impl Eq for Price {}
+
+impl PartialOrd for Price {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Price {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.partial_cmp(&other.0).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Order book kept in sync with the `<symbol>@depth` stream, following the documented
+/// procedure: diff events are buffered, a REST snapshot is applied, events older than
+/// the snapshot are dropped, and the continuity of the update ids is checked for every
+/// event (with `pu` on futures, `U` on spot).
+///
+/// A gap is reported as `DepthUpdate::Gap`, after which a new snapshot is needed.
+#[derive(Debug, Clone)]
+pub struct LocalOrderBook {
+    symbol: String,
+    // None until a snapshot is applied
+    last_update_id: Option<u64>,
+    // Whether an event was applied on top of the snapshot yet
+    bridged: bool,
+    bids: BTreeMap<Price, Num>,
+    asks: BTreeMap<Price, Num>,
+    buffer: VecDeque<DepthOrderBookEvent>,
+    max_buffered: usize,
+}
+
+impl LocalOrderBook {
+    pub fn new<S: Into<String>>(symbol: S) -> Self {
+        LocalOrderBook {
+            symbol: symbol.into().to_uppercase(),
+            last_update_id: None,
+            bridged: false,
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            buffer: VecDeque::new(),
+            max_buffered: 1000,
+        }
+    }
+
+    /// Maximum number of events buffered while waiting for a snapshot, the oldest
+    /// are dropped first (1000 by default).
+    pub fn set_max_buffered(mut self, max_buffered: usize) -> Self {
+        self.max_buffered = max_buffered;
+        self
+    }
+
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// True once a snapshot was applied and no gap was detected since.
+    pub fn is_synced(&self) -> bool {
+        self.last_update_id.is_some()
+    }
+
+    pub fn last_update_id(&self) -> Option<u64> {
+        self.last_update_id
+    }
+
+    /// Applies a diff depth event of the symbol.
+    pub fn update(&mut self, event: DepthOrderBookEvent) -> DepthUpdate {
+        if event.symbol.to_uppercase() != self.symbol {
+            return DepthUpdate::Stale;
+        }
+
+        let last_update_id = match self.last_update_id {
+            Some(last_update_id) => last_update_id,
+            None => {
+                if self.buffer.len() >= self.max_buffered {
+                    self.buffer.pop_front();
+                }
+                self.buffer.push_back(event);
+                return DepthUpdate::Buffered;
+            }
+        };
+
+        if is_stale(&event, last_update_id, self.bridged) {
+            return DepthUpdate::Stale;
+        }
+        let continuous = if self.bridged {
+            follows(&event, last_update_id)
+        } else {
+            bridges_snapshot(&event, last_update_id)
+        };
+        if !continuous {
+            let gap = DepthUpdate::Gap {
+                last_update_id,
+                first_update_id: event.first_update_id,
+            };
+            self.reset();
+            self.buffer.push_back(event);
+            return gap;
+        }
+
+        self.apply(&event);
+        DepthUpdate::Applied
+    }
+
+    /// Replaces the book with a REST snapshot, then applies the buffered events.
+    ///
+    /// Returns `DepthUpdate::Gap` if the buffered events don't continue the snapshot,
+    /// e.g. when the snapshot is older than the first buffered event.
+    pub fn apply_snapshot<T: DepthSnapshot>(&mut self, snapshot: &T) -> DepthUpdate {
+        self.bids = snapshot
+            .bids()
+            .iter()
+            .map(|bid| (Price(bid.price), bid.qty))
+            .collect();
+        self.asks = snapshot
+            .asks()
+            .iter()
+            .map(|ask| (Price(ask.price), ask.qty))
+            .collect();
+        self.last_update_id = Some(snapshot.last_update_id());
+        self.bridged = false;
+
+        let mut buffer: VecDeque<DepthOrderBookEvent> = self.buffer.drain(..).collect();
+        while let Some(event) = buffer.pop_front() {
+            if let gap @ DepthUpdate::Gap { .. } = self.update(event) {
+                // Keep the events after the gap for the next snapshot
+                self.buffer.extend(buffer);
+                return gap;
+            }
+        }
+
+        DepthUpdate::Applied
+    }
+
+    /// Downloads a snapshot with `get_depth` of a spot `Market` or a `FuturesMarket`
+    /// and applies it.
+    pub fn sync<M: DepthSnapshotSource>(&mut self, market: &M) -> Result<DepthUpdate> {
+        let snapshot = market.depth_snapshot(self.symbol.as_str())?;
+
+        Ok(self.apply_snapshot(&snapshot))
+    }
+
+    /// Clears the book, which then buffers the events until the next snapshot.
+    pub fn reset(&mut self) {
+        self.last_update_id = None;
+        self.bridged = false;
+        self.bids.clear();
+        self.asks.clear();
+        self.buffer.clear();
+    }
+
+    pub fn best_bid(&self) -> Option<PriceLevel> {
+        self.bids.iter().next_back().map(to_level)
+    }
+
+    pub fn best_ask(&self) -> Option<PriceLevel> {
+        self.asks.iter().next().map(to_level)
+    }
+
+    pub fn spread(&self) -> Option<Num> {
+        match (self.best_bid(), self.best_ask()) {
+            (Some(bid), Some(ask)) => Some(ask.price - bid.price),
+            _ => None,
+        }
+    }
+
+    /// Best `levels` bids, highest price first.
+    pub fn bids(&self, levels: usize) -> Vec<PriceLevel> {
+        self.bids.iter().rev().take(levels).map(to_level).collect()
+    }
+
+    /// Best `levels` asks, lowest price first.
+    pub fn asks(&self, levels: usize) -> Vec<PriceLevel> {
+        self.asks.iter().take(levels).map(to_level).collect()
+    }
+
+    /// Average price of a market order of `qty` walking the book, asks for a buy
+    /// and bids for a sell. `None` if the book is not deep enough.
+    pub fn vwap_to_fill(&self, side: OrderSide, qty: Num) -> Option<Num> {
+        let levels: Box<dyn Iterator<Item = (&Price, &Num)>> = match side {
+            OrderSide::Buy => Box::new(self.asks.iter()),
+            OrderSide::Sell => Box::new(self.bids.iter().rev()),
+            _ => return None,
+        };
+        let zero = Num::default();
+        if qty <= zero {
+            return None;
+        }
+
+        let mut remaining = qty;
+        let mut cost = zero;
+        for (price, level_qty) in levels {
+            let filled = remaining.min(*level_qty);
+            cost += price.0 * filled;
+            remaining -= filled;
+            if remaining <= zero {
+                return Some(cost / qty);
+            }
+        }
+
+        None
+    }
+
+    fn apply(&mut self, event: &DepthOrderBookEvent) {
+        for bid in &event.bids {
+            update_level(&mut self.bids, bid.price, bid.qty);
+        }
+        for ask in &event.asks {
+            update_level(&mut self.asks, ask.price, ask.qty);
+        }
+        self.last_update_id = Some(event.final_update_id);
+        self.bridged = true;
+    }
+}
+
+fn update_level(levels: &mut BTreeMap<Price, Num>, price: Num, qty: Num) {
+    if qty == Num::default() {
+        levels.remove(&Price(price));
+    } else {
+        levels.insert(Price(price), qty);
+    }
+}
+
+fn to_level((price, qty): (&Price, &Num)) -> PriceLevel {
+    PriceLevel {
+        price: price.0,
+        qty: *qty,
+    }
+}
+
+// Futures events carry `pu`, their update ids are not consecutive across events
+fn is_stale(event: &DepthOrderBookEvent, last_update_id: u64, bridged: bool) -> bool {
+    match event.previous_final_update_id {
+        Some(_) if !bridged => event.final_update_id < last_update_id,
+        _ => event.final_update_id <= last_update_id,
+    }
+}
+
+fn bridges_snapshot(event: &DepthOrderBookEvent, last_update_id: u64) -> bool {
+    match event.previous_final_update_id {
+        Some(_) => {
+            event.first_update_id <= last_update_id && event.final_update_id >= last_update_id
+        }
+        None => {
+            event.first_update_id <= last_update_id + 1 && event.final_update_id > last_update_id
+        }
+    }
+}
+
+fn follows(event: &DepthOrderBookEvent, last_update_id: u64) -> bool {
+    match event.previous_final_update_id {
+        Some(previous_final_update_id) => previous_final_update_id == last_update_id,
+        None => event.first_update_id == last_update_id + 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn num(value: &str) -> Num {
+        value.parse().unwrap()
+    }
+
+    fn snapshot(last_update_id: u64) -> spot::OrderBook {
+        serde_json::from_value(json!({
+            "lastUpdateId": last_update_id,
+            "bids": [["99.0", "1.0"], ["98.0", "2.0"]],
+            "asks": [["101.0", "1.0"], ["102.0", "2.0"]]
+        }))
+        .unwrap()
+    }
+
+    fn futures_snapshot(last_update_id: u64) -> futures::OrderBook {
+        serde_json::from_value(json!({
+            "lastUpdateId": last_update_id,
+            "E": 1,
+            "T": 1,
+            "bids": [["99.0", "1.0"]],
+            "asks": [["101.0", "1.0"]]
+        }))
+        .unwrap()
+    }
+
+    // Spot event without `pu`
+    fn event(first: u64, last: u64, bids: &[(&str, &str)], asks: &[(&str, &str)]) -> DepthOrderBookEvent {
+        serde_json::from_value(json!({
+            "e": "depthUpdate",
+            "E": 1,
+            "s": "BTCUSDT",
+            "U": first,
+            "u": last,
+            "b": bids,
+            "a": asks
+        }))
+        .unwrap()
+    }
+
+    fn futures_event(first: u64, last: u64, previous: u64) -> DepthOrderBookEvent {
+        let mut event = event(first, last, &[], &[]);
+        event.previous_final_update_id = Some(previous);
+        event
+    }
+
+    fn level(price: &str, qty: &str) -> PriceLevel {
+        PriceLevel {
+            price: num(price),
+            qty: num(qty),
+        }
+    }
+
+    #[test]
+    fn spot_events_bridge_the_snapshot() {
+        let mut book = LocalOrderBook::new("btcusdt");
+
+        assert_eq!(book.update(event(90, 95, &[], &[])), DepthUpdate::Buffered);
+        assert_eq!(book.update(event(96, 101, &[("99.0", "3.0")], &[])), DepthUpdate::Buffered);
+        assert!(!book.is_synced());
+
+        // The first event is older than the snapshot, the second one contains U <= 101 <= u
+        assert_eq!(book.apply_snapshot(&snapshot(100)), DepthUpdate::Applied);
+        assert_eq!(book.last_update_id(), Some(101));
+        assert_eq!(book.best_bid(), Some(level("99.0", "3.0")));
+
+        assert_eq!(book.update(event(102, 105, &[], &[("100.5", "1.0")])), DepthUpdate::Applied);
+        assert_eq!(book.last_update_id(), Some(105));
+        assert_eq!(book.best_ask(), Some(level("100.5", "1.0")));
+        assert_eq!(book.spread(), Some(num("1.5")));
+    }
+
+    #[test]
+    fn spot_first_event_must_contain_the_snapshot_id() {
+        let mut book = LocalOrderBook::new("BTCUSDT");
+        book.update(event(103, 105, &[], &[]));
+
+        assert_eq!(
+            book.apply_snapshot(&snapshot(100)),
+            DepthUpdate::Gap {
+                last_update_id: 100,
+                first_update_id: 103
+            }
+        );
+        assert!(!book.is_synced());
+
+        // The event is kept for the next snapshot
+        assert_eq!(book.apply_snapshot(&snapshot(104)), DepthUpdate::Applied);
+        assert_eq!(book.last_update_id(), Some(105));
+    }
+
+    #[test]
+    fn stale_events_dropped() {
+        let mut book = LocalOrderBook::new("BTCUSDT");
+        book.apply_snapshot(&snapshot(100));
+
+        assert_eq!(book.update(event(95, 100, &[("99.0", "5.0")], &[])), DepthUpdate::Stale);
+        assert_eq!(book.best_bid(), Some(level("99.0", "1.0")));
+        assert_eq!(book.update(event(101, 102, &[], &[])), DepthUpdate::Applied);
+        assert_eq!(book.update(event(101, 102, &[], &[])), DepthUpdate::Stale);
+        // Other symbol
+        let mut other = event(103, 104, &[], &[]);
+        other.symbol = "ETHUSDT".into();
+        assert_eq!(book.update(other), DepthUpdate::Stale);
+        assert_eq!(book.last_update_id(), Some(102));
+    }
+
+    #[test]
+    fn spot_gap_reported() {
+        let mut book = LocalOrderBook::new("BTCUSDT");
+        book.apply_snapshot(&snapshot(100));
+        book.update(event(101, 105, &[], &[]));
+
+        assert_eq!(
+            book.update(event(107, 110, &[], &[])),
+            DepthUpdate::Gap {
+                last_update_id: 105,
+                first_update_id: 107
+            }
+        );
+        assert!(!book.is_synced());
+        assert_eq!(book.best_bid(), None);
+        assert_eq!(book.update(event(111, 112, &[], &[])), DepthUpdate::Buffered);
+    }
+
+    #[test]
+    fn futures_events_follow_pu() {
+        let mut book = LocalOrderBook::new("BTCUSDT");
+        book.update(futures_event(90, 99, 89));
+        book.update(futures_event(95, 102, 99));
+
+        // u < lastUpdateId is dropped, then U <= 100 <= u
+        assert_eq!(book.apply_snapshot(&futures_snapshot(100)), DepthUpdate::Applied);
+        assert_eq!(book.last_update_id(), Some(102));
+
+        // Not consecutive update ids, but pu is the previous u
+        assert_eq!(book.update(futures_event(110, 115, 102)), DepthUpdate::Applied);
+        assert_eq!(book.update(futures_event(110, 115, 102)), DepthUpdate::Stale);
+        assert_eq!(
+            book.update(futures_event(120, 125, 118)),
+            DepthUpdate::Gap {
+                last_update_id: 115,
+                first_update_id: 120
+            }
+        );
+    }
+
+    #[test]
+    fn futures_event_ending_at_the_snapshot_bridges() {
+        let mut book = LocalOrderBook::new("BTCUSDT");
+        book.update(futures_event(95, 100, 94));
+
+        assert_eq!(book.apply_snapshot(&futures_snapshot(100)), DepthUpdate::Applied);
+        assert_eq!(book.update(futures_event(101, 103, 100)), DepthUpdate::Applied);
+    }
+
+    #[test]
+    fn zero_qty_removes_the_level() {
+        let mut book = LocalOrderBook::new("BTCUSDT");
+        book.apply_snapshot(&snapshot(100));
+
+        book.update(event(101, 101, &[("99.0", "0")], &[("101.0", "0.00000000")]));
+        assert_eq!(book.bids(5), vec![level("98.0", "2.0")]);
+        assert_eq!(book.asks(5), vec![level("102.0", "2.0")]);
+    }
+
+    #[test]
+    fn levels_sorted_from_the_best() {
+        let mut book = LocalOrderBook::new("BTCUSDT");
+        book.apply_snapshot(&snapshot(100));
+        book.update(event(101, 101, &[("97.5", "1.0")], &[("101.5", "1.0")]));
+
+        assert_eq!(
+            book.bids(2),
+            vec![level("99.0", "1.0"), level("98.0", "2.0")]
+        );
+        assert_eq!(
+            book.asks(3),
+            vec![level("101.0", "1.0"), level("101.5", "1.0"), level("102.0", "2.0")]
+        );
+    }
+
+    #[test]
+    fn vwap_to_fill() {
+        let mut book = LocalOrderBook::new("BTCUSDT");
+        book.apply_snapshot(&snapshot(100));
+
+        assert_eq!(book.vwap_to_fill(OrderSide::Buy, num("0.5")), Some(num("101")));
+        // 101 * 1 + 102 * 1
+        assert_eq!(book.vwap_to_fill(OrderSide::Buy, num("2")), Some(num("101.5")));
+        // 99 * 1 + 98 * 1
+        assert_eq!(book.vwap_to_fill(OrderSide::Sell, num("2")), Some(num("98.5")));
+        assert_eq!(book.vwap_to_fill(OrderSide::Buy, num("3.5")), None);
+        assert_eq!(book.vwap_to_fill(OrderSide::Buy, num("0")), None);
+    }
+
+    #[test]
+    fn oldest_buffered_events_dropped() {
+        let mut book = LocalOrderBook::new("BTCUSDT").set_max_buffered(2);
+        book.update(event(101, 101, &[], &[]));
+        book.update(event(102, 102, &[], &[]));
+        book.update(event(103, 103, &[], &[]));
+
+        // 101 was dropped
+        assert_eq!(
+            book.apply_snapshot(&snapshot(100)),
+            DepthUpdate::Gap {
+                last_update_id: 100,
+                first_update_id: 102
+            }
+        );
+    }
+}
@@ -0,0 +1,393 @@
+use crate::errors::*;
+use crate::model::RateLimit;
+use reqwest::header::HeaderMap;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+static USED_WEIGHT_HEADER: &str = "x-mbx-used-weight-";
+static ORDER_COUNT_HEADER: &str = "x-mbx-order-count-";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RateLimitType {
+    RequestWeight,
+    Orders,
+}
+
+impl RateLimitType {
+    fn from_exchange(rate_limit_type: &str) -> Option<Self> {
+        match rate_limit_type {
+            "REQUEST_WEIGHT" => Some(RateLimitType::RequestWeight),
+            "ORDERS" => Some(RateLimitType::Orders),
+            _ => None,
+        }
+    }
+}
+
+/// What the client does before a request when the usage reached the budget.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RateLimitPolicy {
+    /// Only record the usage reported by the exchange.
+    Track,
+    /// Fail with `ErrorKind::RateLimitBudgetExceeded` without sending the request.
+    FailFast,
+    /// Wait until the exhausted interval resets, then send the request. Waits longer
+    /// than the limiter's `max_wait` fail like `FailFast`.
+    Block,
+}
+
+/// Usage of one rate limit interval, as last reported by the exchange.
+#[derive(Debug, Clone)]
+pub struct RateLimitUsage {
+    pub rate_limit_type: RateLimitType,
+    /// Length of the interval, e.g. 60 seconds for `X-MBX-USED-WEIGHT-1M`.
+    pub interval: Duration,
+    pub used: u64,
+    /// Limit from `ExchangeInformation::rate_limits`, if known.
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+struct Counter {
+    used: u64,
+    // Index of the interval window the usage was reported in
+    window: u64,
+    limit: Option<u64>,
+}
+
+/// Tracks the request weight and order count reported in the
+/// `X-MBX-USED-WEIGHT-*` and `X-MBX-ORDER-COUNT-*` response headers.
+///
+/// Each client has its own limiter, see `Config::set_rate_limiter` to share one.
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    policy: RateLimitPolicy,
+    budget: f64,
+    max_wait: Duration,
+    counters: Arc<Mutex<HashMap<(RateLimitType, u64), Counter>>>,
+}
+
+impl RateLimiter {
+    /// `budget` is the fraction (0.0 - 1.0) of each exchange limit the client may use.
+    pub fn new(policy: RateLimitPolicy, budget: f64) -> Self {
+        RateLimiter {
+            policy,
+            budget,
+            max_wait: Duration::from_secs(60),
+            counters: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Longest wait of the `Block` policy, 60 seconds by default.
+    pub fn set_max_wait(mut self, max_wait: Duration) -> Self {
+        self.max_wait = max_wait;
+        self
+    }
+
+    /// Sets the limits to compare the usage with, usually from `exchange_info()`.
+    pub fn set_limits(&self, rate_limits: &[RateLimit]) {
+        let mut counters = self.counters.lock().unwrap();
+        for rate_limit in rate_limits {
+            let rate_limit_type = match RateLimitType::from_exchange(&rate_limit.rate_limit_type) {
+                Some(rate_limit_type) => rate_limit_type,
+                None => continue,
+            };
+            let interval = match interval_from_exchange(&rate_limit.interval) {
+                Some(seconds) if rate_limit.interval_num > 0 => {
+                    seconds * u64::from(rate_limit.interval_num)
+                }
+                _ => continue,
+            };
+            counters
+                .entry((rate_limit_type, interval))
+                .or_default()
+                .limit = Some(rate_limit.limit);
+        }
+    }
+
+    /// Current usage per interval. Intervals which reset since the last response count as unused.
+    pub fn usage(&self) -> Vec<RateLimitUsage> {
+        let now = now_secs();
+        let counters = self.counters.lock().unwrap();
+        counters
+            .iter()
+            .map(|(&(rate_limit_type, interval), counter)| RateLimitUsage {
+                rate_limit_type,
+                interval: Duration::from_secs(interval),
+                used: current_usage(counter, interval, now),
+                limit: counter.limit,
+            })
+            .collect()
+    }
+
+    pub(crate) fn update(&self, headers: &HeaderMap) {
+        let now = now_secs();
+        let mut counters = self.counters.lock().unwrap();
+        for (name, value) in headers {
+            let name = name.as_str();
+            let (rate_limit_type, interval) = if let Some(interval) =
+                name.strip_prefix(USED_WEIGHT_HEADER)
+            {
+                (RateLimitType::RequestWeight, interval)
+            } else if let Some(interval) = name.strip_prefix(ORDER_COUNT_HEADER) {
+                (RateLimitType::Orders, interval)
+            } else {
+                continue;
+            };
+            let interval = match interval_from_header(interval) {
+                Some(interval) => interval,
+                None => continue,
+            };
+            let used = match value.to_str().ok().and_then(|v| v.parse().ok()) {
+                Some(used) => used,
+                None => continue,
+            };
+
+            let counter = counters.entry((rate_limit_type, interval)).or_default();
+            counter.used = used;
+            counter.window = now / interval;
+        }
+    }
+
+    /// Checks the budget before sending a request. `orders` is set for requests
+    /// which place orders, so the order count is checked as well.
+    ///
+    /// Returns how long to wait before sending when the policy is `Block`.
+    pub(crate) fn check(&self, orders: bool) -> Result<Option<Duration>> {
+        if self.policy == RateLimitPolicy::Track {
+            return Ok(None);
+        }
+
+        let now = now_secs();
+        let counters = self.counters.lock().unwrap();
+        let mut wait: Option<(Duration, RateLimitUsage)> = None;
+        for (&(rate_limit_type, interval), counter) in counters.iter() {
+            if rate_limit_type == RateLimitType::Orders && !orders {
+                continue;
+            }
+            let limit = match counter.limit {
+                Some(limit) => limit,
+                None => continue,
+            };
+            let used = current_usage(counter, interval, now);
+            if (used as f64) < (limit as f64) * self.budget {
+                continue;
+            }
+
+            let usage = RateLimitUsage {
+                rate_limit_type,
+                interval: Duration::from_secs(interval),
+                used,
+                limit: Some(limit),
+            };
+            if self.policy == RateLimitPolicy::FailFast {
+                return Err(ErrorKind::RateLimitBudgetExceeded(usage).into());
+            }
+            let reset = Duration::from_secs(interval - now % interval);
+            match wait {
+                Some((longest, _)) if longest >= reset => {}
+                _ => wait = Some((reset, usage)),
+            }
+        }
+
+        match wait {
+            // E.g. the daily order count, rather fail than block for hours
+            Some((reset, usage)) if reset > self.max_wait => {
+                Err(ErrorKind::RateLimitBudgetExceeded(usage).into())
+            }
+            wait => Ok(wait.map(|(reset, _)| reset)),
+        }
+    }
+}
+
+fn current_usage(counter: &Counter, interval: u64, now: u64) -> u64 {
+    // Binance counts usage in fixed windows aligned on the interval
+    if counter.window == now / interval {
+        counter.used
+    } else {
+        0
+    }
+}
+
+// "1m", "10s", "1d", ...
+fn interval_from_header(interval: &str) -> Option<u64> {
+    if interval.len() < 2 {
+        return None;
+    }
+    let (num, unit) = interval.split_at(interval.len() - 1);
+    let num: u64 = num.parse().ok().filter(|&num| num > 0)?;
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => return None,
+    };
+
+    Some(num * seconds)
+}
+
+fn interval_from_exchange(interval: &str) -> Option<u64> {
+    match interval {
+        "SECOND" => Some(1),
+        "MINUTE" => Some(60),
+        "HOUR" => Some(3600),
+        "DAY" => Some(86400),
+        _ => None,
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(values: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for &(name, value) in values {
+            headers.insert(name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    fn rate_limit(rate_limit_type: &str, interval: &str, interval_num: u16, limit: u64) -> RateLimit {
+        RateLimit {
+            rate_limit_type: rate_limit_type.into(),
+            interval: interval.into(),
+            interval_num,
+            limit,
+        }
+    }
+
+    fn used(limiter: &RateLimiter, rate_limit_type: RateLimitType, seconds: u64) -> Option<u64> {
+        limiter
+            .usage()
+            .into_iter()
+            .find(|usage| {
+                usage.rate_limit_type == rate_limit_type
+                    && usage.interval == Duration::from_secs(seconds)
+            })
+            .map(|usage| usage.used)
+    }
+
+    #[test]
+    fn interval_from_header_units() {
+        assert_eq!(interval_from_header("1m"), Some(60));
+        assert_eq!(interval_from_header("10s"), Some(10));
+        assert_eq!(interval_from_header("1h"), Some(3600));
+        assert_eq!(interval_from_header("1d"), Some(86400));
+        assert_eq!(interval_from_header("0m"), None);
+        assert_eq!(interval_from_header("m"), None);
+        assert_eq!(interval_from_header("1w"), None);
+        assert_eq!(interval_from_header("xm"), None);
+    }
+
+    #[test]
+    fn update_parses_the_usage_headers() {
+        let limiter = RateLimiter::new(RateLimitPolicy::Track, 1.0);
+        limiter.update(&headers(&[
+            ("x-mbx-used-weight-1m", "120"),
+            ("x-mbx-order-count-10s", "3"),
+            ("x-mbx-order-count-1d", "25"),
+            ("x-mbx-used-weight-1w", "1"),
+            ("x-mbx-used-weight-1h", "not a number"),
+            ("content-type", "application/json"),
+        ]));
+
+        assert_eq!(used(&limiter, RateLimitType::RequestWeight, 60), Some(120));
+        assert_eq!(used(&limiter, RateLimitType::Orders, 10), Some(3));
+        assert_eq!(used(&limiter, RateLimitType::Orders, 86400), Some(25));
+        assert_eq!(limiter.usage().len(), 3);
+    }
+
+    #[test]
+    fn set_limits_from_exchange_info() {
+        let limiter = RateLimiter::new(RateLimitPolicy::Track, 1.0);
+        limiter.set_limits(&[
+            rate_limit("REQUEST_WEIGHT", "MINUTE", 1, 2400),
+            rate_limit("ORDERS", "SECOND", 10, 300),
+            rate_limit("RAW_REQUESTS", "MINUTE", 1, 6100),
+        ]);
+
+        let mut limits: Vec<_> = limiter
+            .usage()
+            .into_iter()
+            .map(|usage| (usage.rate_limit_type, usage.interval.as_secs(), usage.limit))
+            .collect();
+        limits.sort_by_key(|&(_, interval, _)| interval);
+        assert_eq!(
+            limits,
+            vec![
+                (RateLimitType::Orders, 10, Some(300)),
+                (RateLimitType::RequestWeight, 60, Some(2400)),
+            ]
+        );
+    }
+
+    #[test]
+    fn fail_fast_over_budget() {
+        let limiter = RateLimiter::new(RateLimitPolicy::FailFast, 0.5);
+        limiter.set_limits(&[rate_limit("REQUEST_WEIGHT", "DAY", 1, 100)]);
+
+        limiter.update(&headers(&[("x-mbx-used-weight-1d", "49")]));
+        assert!(limiter.check(false).unwrap().is_none());
+
+        limiter.update(&headers(&[("x-mbx-used-weight-1d", "50")]));
+        match limiter.check(false) {
+            Err(Error(ErrorKind::RateLimitBudgetExceeded(usage), _)) => {
+                assert_eq!(usage.used, 50);
+                assert_eq!(usage.limit, Some(100));
+            }
+            _ => panic!("expected RateLimitBudgetExceeded"),
+        }
+    }
+
+    #[test]
+    fn order_count_only_checked_for_orders() {
+        let limiter = RateLimiter::new(RateLimitPolicy::FailFast, 1.0);
+        limiter.set_limits(&[rate_limit("ORDERS", "DAY", 1, 10)]);
+        limiter.update(&headers(&[("x-mbx-order-count-1d", "10")]));
+
+        assert!(limiter.check(false).unwrap().is_none());
+        assert!(limiter.check(true).is_err());
+    }
+
+    #[test]
+    fn block_waits_until_the_window_resets() {
+        let limiter = RateLimiter::new(RateLimitPolicy::Block, 1.0)
+            .set_max_wait(Duration::from_secs(3600));
+        limiter.set_limits(&[rate_limit("REQUEST_WEIGHT", "MINUTE", 1, 10)]);
+        limiter.update(&headers(&[("x-mbx-used-weight-1m", "10")]));
+
+        let wait = limiter.check(false).unwrap().unwrap();
+        assert!(wait > Duration::from_secs(0) && wait <= Duration::from_secs(60));
+    }
+
+    #[test]
+    fn block_fails_beyond_max_wait() {
+        let limiter = RateLimiter::new(RateLimitPolicy::Block, 1.0);
+        limiter.set_limits(&[rate_limit("ORDERS", "DAY", 1, 10)]);
+        limiter.update(&headers(&[("x-mbx-order-count-1d", "10")]));
+
+        // Unless the test runs within a minute of midnight UTC
+        if now_secs() % 86400 < 86400 - 60 {
+            assert!(limiter.check(true).is_err());
+        }
+    }
+
+    #[test]
+    fn clones_share_the_counters() {
+        let limiter = RateLimiter::new(RateLimitPolicy::Track, 1.0);
+        let clone = limiter.clone();
+        clone.update(&headers(&[("x-mbx-used-weight-1m", "7")]));
+
+        assert_eq!(used(&limiter, RateLimitType::RequestWeight, 60), Some(7));
+    }
+}
@@ -0,0 +1,306 @@
+use crate::account::NewOrder;
+use crate::errors::*;
+use crate::futures::model as futures;
+use crate::model::{self as spot, Filters};
+use crate::symbolrules::SymbolRules;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// Exchange information the registry can index, spot or futures.
+pub trait ExchangeSymbols: Send + Sync + 'static {
+    type Symbol: SymbolInfo + Clone;
+
+    fn symbols(&self) -> &[Self::Symbol];
+}
+
+pub trait SymbolInfo {
+    fn name(&self) -> &str;
+    fn base_asset(&self) -> &str;
+    fn quote_asset(&self) -> &str;
+    fn filters(&self) -> &[Filters];
+}
+
+impl ExchangeSymbols for spot::ExchangeInformation {
+    type Symbol = spot::Symbol;
+
+    fn symbols(&self) -> &[spot::Symbol] {
+        &self.symbols
+    }
+}
+
+impl ExchangeSymbols for futures::ExchangeInformation {
+    type Symbol = futures::Symbol;
+
+    fn symbols(&self) -> &[futures::Symbol] {
+        &self.symbols
+    }
+}
+
+impl SymbolInfo for spot::Symbol {
+    fn name(&self) -> &str {
+        &self.symbol
+    }
+
+    fn base_asset(&self) -> &str {
+        &self.base_asset
+    }
+
+    fn quote_asset(&self) -> &str {
+        &self.quote_asset
+    }
+
+    fn filters(&self) -> &[Filters] {
+        &self.filters
+    }
+}
+
+impl SymbolInfo for futures::Symbol {
+    fn name(&self) -> &str {
+        &self.symbol
+    }
+
+    fn base_asset(&self) -> &str {
+        &self.base_asset
+    }
+
+    fn quote_asset(&self) -> &str {
+        &self.quote_asset
+    }
+
+    fn filters(&self) -> &[Filters] {
+        &self.filters
+    }
+}
+
+pub type SpotSymbolRegistry = SymbolRegistry<spot::ExchangeInformation>;
+pub type FuturesSymbolRegistry = SymbolRegistry<futures::ExchangeInformation>;
+
+type Loader<I> = Arc<dyn Fn() -> Result<I> + Send + Sync>;
+
+struct Cache<I: ExchangeSymbols> {
+    info: Arc<I>,
+    loaded: Instant,
+    stale: bool,
+    // Indexes into `info.symbols()`
+    by_name: HashMap<String, usize>,
+    by_base_asset: HashMap<String, Vec<usize>>,
+    by_quote_asset: HashMap<String, Vec<usize>>,
+    rules: HashMap<String, SymbolRules>,
+}
+
+impl<I: ExchangeSymbols> Cache<I> {
+    fn new(info: I) -> Self {
+        let mut by_name = HashMap::new();
+        let mut by_base_asset: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut by_quote_asset: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut rules = HashMap::new();
+
+        for (index, symbol) in info.symbols().iter().enumerate() {
+            by_name.insert(symbol.name().to_string(), index);
+            by_base_asset
+                .entry(symbol.base_asset().to_string())
+                .or_default()
+                .push(index);
+            by_quote_asset
+                .entry(symbol.quote_asset().to_string())
+                .or_default()
+                .push(index);
+            rules.insert(
+                symbol.name().to_string(),
+                SymbolRules::new(symbol.name(), symbol.filters()),
+            );
+        }
+
+        Cache {
+            info: Arc::new(info),
+            loaded: Instant::now(),
+            stale: false,
+            by_name,
+            by_base_asset,
+            by_quote_asset,
+            rules,
+        }
+    }
+
+    fn symbols(&self, indexes: Option<&Vec<usize>>) -> Vec<I::Symbol> {
+        let symbols = self.info.symbols();
+        indexes
+            .map(|indexes| indexes.iter().map(|&index| symbols[index].clone()).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Caches the exchange information, indexed by symbol name and by base and quote asset,
+/// so symbol lookups don't download the whole `exchangeInfo` payload.
+///
+/// The cache is refreshed with the loader once `ttl` elapsed, or on demand with `refresh`.
+/// A failed refresh keeps serving the previous exchange information for another TTL.
+pub struct SymbolRegistry<I: ExchangeSymbols> {
+    cache: Arc<RwLock<Option<Cache<I>>>>,
+    // Serializes the refreshes, so expiry doesn't trigger a download per thread
+    refreshing: Arc<Mutex<()>>,
+    loader: Option<Loader<I>>,
+    ttl: Duration,
+}
+
+impl<I: ExchangeSymbols> Clone for SymbolRegistry<I> {
+    fn clone(&self) -> Self {
+        SymbolRegistry {
+            cache: self.cache.clone(),
+            refreshing: self.refreshing.clone(),
+            loader: self.loader.clone(),
+            ttl: self.ttl,
+        }
+    }
+}
+
+impl<I: ExchangeSymbols> SymbolRegistry<I> {
+    /// Registry without loader, filled with `load`, e.g. from an async client.
+    pub fn new(ttl: Duration) -> Self {
+        SymbolRegistry {
+            cache: Arc::new(RwLock::new(None)),
+            refreshing: Arc::new(Mutex::new(())),
+            loader: None,
+            ttl,
+        }
+    }
+
+    /// Registry downloading the exchange information with `loader` when needed,
+    /// see `General::symbol_registry` and `FuturesGeneral::symbol_registry`.
+    pub fn with_loader<F>(ttl: Duration, loader: F) -> Self
+    where
+        F: Fn() -> Result<I> + Send + Sync + 'static,
+    {
+        SymbolRegistry {
+            loader: Some(Arc::new(loader)),
+            ..SymbolRegistry::new(ttl)
+        }
+    }
+
+    /// Replaces the cached exchange information.
+    pub fn load(&self, info: I) {
+        let cache = Cache::new(info);
+        *self.cache.write().unwrap() = Some(cache);
+    }
+
+    /// Downloads the exchange information now.
+    pub fn refresh(&self) -> Result<()> {
+        let loader = match self.loader {
+            Some(ref loader) => loader,
+            None => bail!("Symbol registry has no loader"),
+        };
+        let _refreshing = self.refreshing.lock().unwrap();
+        let info = loader()?;
+        self.load(info);
+
+        Ok(())
+    }
+
+    /// Forces a refresh on the next lookup.
+    pub fn invalidate(&self) {
+        if let Some(cache) = self.cache.write().unwrap().as_mut() {
+            cache.stale = true;
+        }
+    }
+
+    /// True when the registry is empty, invalidated, or older than the TTL.
+    pub fn is_expired(&self) -> bool {
+        match *self.cache.read().unwrap() {
+            Some(ref cache) => cache.stale || cache.loaded.elapsed() >= self.ttl,
+            None => true,
+        }
+    }
+
+    pub fn exchange_info(&self) -> Result<Arc<I>> {
+        self.read(|cache| Ok(cache.info.clone()))
+    }
+
+    pub fn get_symbol_info<S>(&self, symbol: S) -> Result<I::Symbol>
+    where
+        S: Into<String>,
+    {
+        let upper_symbol = symbol.into().to_uppercase();
+        self.read(|cache| match cache.by_name.get(&upper_symbol) {
+            Some(&index) => Ok(cache.info.symbols()[index].clone()),
+            None => bail!("Symbol not found"),
+        })
+    }
+
+    /// Symbols with `asset` as base asset, e.g. every BTC pair for "BTC".
+    pub fn get_symbols_by_base_asset<S>(&self, asset: S) -> Result<Vec<I::Symbol>>
+    where
+        S: Into<String>,
+    {
+        let asset = asset.into().to_uppercase();
+        self.read(|cache| Ok(cache.symbols(cache.by_base_asset.get(&asset))))
+    }
+
+    /// Symbols with `asset` as quote asset, e.g. every USDT pair for "USDT".
+    pub fn get_symbols_by_quote_asset<S>(&self, asset: S) -> Result<Vec<I::Symbol>>
+    where
+        S: Into<String>,
+    {
+        let asset = asset.into().to_uppercase();
+        self.read(|cache| Ok(cache.symbols(cache.by_quote_asset.get(&asset))))
+    }
+
+    /// Trading rules of the symbol, built once per refresh.
+    pub fn get_rules<S>(&self, symbol: S) -> Result<SymbolRules>
+    where
+        S: Into<String>,
+    {
+        let upper_symbol = symbol.into().to_uppercase();
+        self.read(|cache| match cache.rules.get(&upper_symbol) {
+            Some(rules) => Ok(rules.clone()),
+            None => bail!("Symbol not found"),
+        })
+    }
+
+    /// Checks the order against the filters of its symbol, see `SymbolRules::validate`.
+    pub fn validate(&self, order: &NewOrder) -> Result<()> {
+        self.get_rules(order.symbol())?.validate(order)
+    }
+
+    /// Rounds the order to the filters of its symbol, see `SymbolRules::round_order`.
+    pub fn round_order(&self, order: NewOrder) -> Result<NewOrder> {
+        Ok(self.get_rules(order.symbol())?.round_order(order))
+    }
+
+    fn read<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&Cache<I>) -> Result<T>,
+    {
+        self.refresh_if_expired()?;
+
+        match *self.cache.read().unwrap() {
+            Some(ref cache) => f(cache),
+            None => bail!("Symbol registry is empty"),
+        }
+    }
+
+    fn refresh_if_expired(&self) -> Result<()> {
+        if !self.is_expired() || self.loader.is_none() {
+            return Ok(());
+        }
+
+        let _refreshing = self.refreshing.lock().unwrap();
+        // Another thread may have refreshed while this one waited
+        if !self.is_expired() {
+            return Ok(());
+        }
+        match (self.loader.as_ref().unwrap())() {
+            Ok(info) => self.load(info),
+            Err(e) => match self.cache.write().unwrap().as_mut() {
+                // Retried after another TTL rather than on every lookup
+                Some(cache) => {
+                    cache.loaded = Instant::now();
+                    cache.stale = false;
+                }
+                None => return Err(e),
+            },
+        }
+
+        Ok(())
+    }
+}
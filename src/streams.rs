@@ -0,0 +1,298 @@
+use crate::config::Config;
+use crate::errors::*;
+use crate::model::{ContractType, KlineInterval};
+use crate::websockets::{combined_stream_url, EventKind};
+use std::fmt;
+use url::Url;
+
+/// Update speed of the mark price streams.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkPriceSpeed {
+    Seconds1,
+    /// Default speed
+    Seconds3,
+}
+
+/// Update speed of the depth streams.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepthSpeed {
+    Millis100,
+    /// Default speed
+    Millis250,
+    Millis500,
+}
+
+/// Number of levels of the partial depth streams.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepthLevels {
+    Levels5,
+    Levels10,
+    Levels20,
+}
+
+/// Name of a futures market stream, e.g. `btcusdt@aggTrade` or `!ticker@arr`.
+///
+/// Streams without symbol (`None`) cover every symbol of the market.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamName {
+    AggTrade {
+        symbol: String,
+    },
+    MarkPrice {
+        symbol: Option<String>,
+        speed: MarkPriceSpeed,
+    },
+    Kline {
+        symbol: String,
+        interval: KlineInterval,
+    },
+    ContinuousKline {
+        pair: String,
+        contract_type: ContractType,
+        interval: KlineInterval,
+    },
+    MiniTicker {
+        symbol: Option<String>,
+    },
+    Ticker {
+        symbol: Option<String>,
+    },
+    BookTicker {
+        symbol: Option<String>,
+    },
+    ForceOrder {
+        symbol: Option<String>,
+    },
+    PartialDepth {
+        symbol: String,
+        levels: DepthLevels,
+        speed: DepthSpeed,
+    },
+    DiffDepth {
+        symbol: String,
+        speed: DepthSpeed,
+    },
+    CompositeIndex {
+        symbol: String,
+    },
+}
+
+impl StreamName {
+    pub fn agg_trade<S: Into<String>>(symbol: S) -> Self {
+        StreamName::AggTrade {
+            symbol: symbol.into(),
+        }
+    }
+
+    pub fn mark_price<S: Into<String>>(symbol: S, speed: MarkPriceSpeed) -> Self {
+        StreamName::MarkPrice {
+            symbol: Some(symbol.into()),
+            speed,
+        }
+    }
+
+    pub fn all_mark_prices(speed: MarkPriceSpeed) -> Self {
+        StreamName::MarkPrice {
+            symbol: None,
+            speed,
+        }
+    }
+
+    pub fn kline<S, I>(symbol: S, interval: I) -> Self
+    where
+        S: Into<String>,
+        I: Into<KlineInterval>,
+    {
+        StreamName::Kline {
+            symbol: symbol.into(),
+            interval: interval.into(),
+        }
+    }
+
+    pub fn continuous_kline<S, I>(pair: S, contract_type: ContractType, interval: I) -> Self
+    where
+        S: Into<String>,
+        I: Into<KlineInterval>,
+    {
+        StreamName::ContinuousKline {
+            pair: pair.into(),
+            contract_type,
+            interval: interval.into(),
+        }
+    }
+
+    pub fn mini_ticker<S: Into<String>>(symbol: S) -> Self {
+        StreamName::MiniTicker {
+            symbol: Some(symbol.into()),
+        }
+    }
+
+    pub fn all_mini_tickers() -> Self {
+        StreamName::MiniTicker { symbol: None }
+    }
+
+    pub fn ticker<S: Into<String>>(symbol: S) -> Self {
+        StreamName::Ticker {
+            symbol: Some(symbol.into()),
+        }
+    }
+
+    pub fn all_tickers() -> Self {
+        StreamName::Ticker { symbol: None }
+    }
+
+    pub fn book_ticker<S: Into<String>>(symbol: S) -> Self {
+        StreamName::BookTicker {
+            symbol: Some(symbol.into()),
+        }
+    }
+
+    pub fn all_book_tickers() -> Self {
+        StreamName::BookTicker { symbol: None }
+    }
+
+    pub fn force_order<S: Into<String>>(symbol: S) -> Self {
+        StreamName::ForceOrder {
+            symbol: Some(symbol.into()),
+        }
+    }
+
+    pub fn all_force_orders() -> Self {
+        StreamName::ForceOrder { symbol: None }
+    }
+
+    pub fn partial_depth<S: Into<String>>(
+        symbol: S, levels: DepthLevels, speed: DepthSpeed,
+    ) -> Self {
+        StreamName::PartialDepth {
+            symbol: symbol.into(),
+            levels,
+            speed,
+        }
+    }
+
+    pub fn diff_depth<S: Into<String>>(symbol: S, speed: DepthSpeed) -> Self {
+        StreamName::DiffDepth {
+            symbol: symbol.into(),
+            speed,
+        }
+    }
+
+    pub fn composite_index<S: Into<String>>(symbol: S) -> Self {
+        StreamName::CompositeIndex {
+            symbol: symbol.into(),
+        }
+    }
+
+    /// `WebsocketEvent` variant the stream yields, see `WebsocketEvent::kind`.
+    pub fn event_kind(&self) -> EventKind {
+        match *self {
+            StreamName::AggTrade { .. } => EventKind::Trade,
+            StreamName::MarkPrice { symbol: Some(_), .. } => EventKind::FuturesFunding,
+            StreamName::MarkPrice { symbol: None, .. } => EventKind::MarkPrices,
+            StreamName::Kline { .. } => EventKind::Kline,
+            StreamName::ContinuousKline { .. } => EventKind::ContinuousKline,
+            StreamName::MiniTicker { .. } => EventKind::MiniTicker,
+            StreamName::Ticker { .. } => EventKind::DayTicker,
+            StreamName::BookTicker { .. } => EventKind::BookTicker,
+            StreamName::ForceOrder { .. } => EventKind::Liquidation,
+            // Futures partial depths are sent as diff depth events
+            StreamName::PartialDepth { .. } | StreamName::DiffDepth { .. } => {
+                EventKind::DepthOrderBook
+            }
+            StreamName::CompositeIndex { .. } => EventKind::CompositeIndex,
+        }
+    }
+
+    /// Streams joined for `WebSockets::connect`, e.g. `btcusdt@aggTrade/btcusdt@depth`.
+    pub fn combined_endpoint(streams: &[StreamName]) -> String {
+        streams
+            .iter()
+            .map(|stream| stream.to_string())
+            .collect::<Vec<String>>()
+            .join("/")
+    }
+
+    /// Combined stream URL of the streams on the endpoint of `config`.
+    pub fn combined_url(streams: &[StreamName], config: &Config) -> Result<Url> {
+        combined_stream_url(config, &StreamName::combined_endpoint(streams))
+    }
+}
+
+impl fmt::Display for StreamName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StreamName::AggTrade { ref symbol } => write!(f, "{}@aggTrade", lower(symbol)),
+            StreamName::MarkPrice { ref symbol, speed } => {
+                match *symbol {
+                    Some(ref symbol) => write!(f, "{}@markPrice", lower(symbol))?,
+                    None => write!(f, "!markPrice@arr")?,
+                }
+                match speed {
+                    MarkPriceSpeed::Seconds1 => write!(f, "@1s"),
+                    MarkPriceSpeed::Seconds3 => Ok(()),
+                }
+            }
+            StreamName::Kline {
+                ref symbol,
+                ref interval,
+            } => write!(f, "{}@kline_{}", lower(symbol), interval),
+            StreamName::ContinuousKline {
+                ref pair,
+                ref contract_type,
+                ref interval,
+            } => write!(
+                f,
+                "{}_{}@continuousKline_{}",
+                lower(pair),
+                lower(contract_type.as_str()),
+                interval
+            ),
+            StreamName::MiniTicker { ref symbol } => all_or_symbol(f, symbol, "miniTicker"),
+            StreamName::Ticker { ref symbol } => all_or_symbol(f, symbol, "ticker"),
+            StreamName::BookTicker { ref symbol } => match *symbol {
+                Some(ref symbol) => write!(f, "{}@bookTicker", lower(symbol)),
+                None => write!(f, "!bookTicker"),
+            },
+            StreamName::ForceOrder { ref symbol } => all_or_symbol(f, symbol, "forceOrder"),
+            StreamName::PartialDepth {
+                ref symbol,
+                levels,
+                speed,
+            } => {
+                let levels = match levels {
+                    DepthLevels::Levels5 => 5,
+                    DepthLevels::Levels10 => 10,
+                    DepthLevels::Levels20 => 20,
+                };
+                write!(f, "{}@depth{}{}", lower(symbol), levels, depth_speed(speed))
+            }
+            StreamName::DiffDepth { ref symbol, speed } => {
+                write!(f, "{}@depth{}", lower(symbol), depth_speed(speed))
+            }
+            StreamName::CompositeIndex { ref symbol } => {
+                write!(f, "{}@compositeIndex", lower(symbol))
+            }
+        }
+    }
+}
+
+fn lower(symbol: &str) -> String {
+    symbol.to_lowercase()
+}
+
+// `!<stream>@arr` for every symbol, `<symbol>@<stream>` otherwise
+fn all_or_symbol(f: &mut fmt::Formatter, symbol: &Option<String>, stream: &str) -> fmt::Result {
+    match *symbol {
+        Some(ref symbol) => write!(f, "{}@{}", lower(symbol), stream),
+        None => write!(f, "!{}@arr", stream),
+    }
+}
+
+fn depth_speed(speed: DepthSpeed) -> &'static str {
+    match speed {
+        DepthSpeed::Millis100 => "@100ms",
+        DepthSpeed::Millis250 => "",
+        DepthSpeed::Millis500 => "@500ms",
+    }
+}
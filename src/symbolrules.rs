@@ -0,0 +1,477 @@
+use crate::account::NewOrder;
+use crate::errors::*;
+use crate::futures::model::ExchangeInformation;
+use crate::model::{Filters, Num, NumString, OrderType};
+use std::collections::HashMap;
+
+#[cfg(feature = "decimal")]
+use rust_decimal::RoundingStrategy;
+
+#[derive(Debug, Clone)]
+pub struct PriceFilter {
+    pub min_price: Num,
+    pub max_price: Num,
+    pub tick_size: Num,
+}
+
+#[derive(Debug, Clone)]
+pub struct LotSize {
+    pub min_qty: Num,
+    pub max_qty: Num,
+    pub step_size: Num,
+}
+
+/// Trading rules of one symbol, from the filters of the exchange information.
+///
+/// Orders can be rounded to the tick and step sizes, and checked locally
+/// before being sent, instead of being rejected by the exchange.
+#[derive(Debug, Clone)]
+pub struct SymbolRules {
+    pub symbol: String,
+    pub price_filter: Option<PriceFilter>,
+    pub lot_size: Option<LotSize>,
+    // Applies to MARKET orders instead of `lot_size` when set
+    pub market_lot_size: Option<LotSize>,
+    pub min_notional: Option<Num>,
+}
+
+impl SymbolRules {
+    pub fn new<S: Into<String>>(symbol: S, filters: &[Filters]) -> Self {
+        let mut rules = SymbolRules {
+            symbol: symbol.into(),
+            price_filter: None,
+            lot_size: None,
+            market_lot_size: None,
+            min_notional: None,
+        };
+
+        for filter in filters {
+            match *filter {
+                Filters::PriceFilter {
+                    ref min_price,
+                    ref max_price,
+                    ref tick_size,
+                } => {
+                    if let (Some(min_price), Some(max_price), Some(tick_size)) =
+                        (num(min_price), num(max_price), num(tick_size))
+                    {
+                        rules.price_filter = Some(PriceFilter {
+                            min_price,
+                            max_price,
+                            tick_size,
+                        });
+                    }
+                }
+                Filters::LotSize {
+                    ref min_qty,
+                    ref max_qty,
+                    ref step_size,
+                } => {
+                    rules.lot_size = lot_size(min_qty, max_qty, step_size);
+                }
+                Filters::MarketLotSize {
+                    ref min_qty,
+                    ref max_qty,
+                    ref step_size,
+                } => {
+                    rules.market_lot_size = lot_size(min_qty, max_qty, step_size);
+                }
+                Filters::MinNotional {
+                    ref min_notional, ..
+                } => {
+                    rules.min_notional = num(min_notional);
+                }
+                _ => {}
+            }
+        }
+
+        rules
+    }
+
+    /// Rules of every symbol of the futures exchange information, by symbol name.
+    pub fn from_exchange_info(info: &ExchangeInformation) -> HashMap<String, SymbolRules> {
+        info.symbols
+            .iter()
+            .map(|symbol| {
+                (
+                    symbol.symbol.clone(),
+                    SymbolRules::new(symbol.symbol.as_str(), &symbol.filters),
+                )
+            })
+            .collect()
+    }
+
+    /// Rounds the price to the nearest valid price, a multiple of the tick size above
+    /// the minimum price.
+    pub fn round_price(&self, price: Num) -> Num {
+        match self.price_filter {
+            Some(ref filter) => round_to_step(price, filter.min_price, filter.tick_size),
+            None => price,
+        }
+    }
+
+    /// Rounds the quantity down to a multiple of the step size above the minimum
+    /// quantity, so it never exceeds the requested quantity.
+    pub fn round_qty(&self, qty: Num, market: bool) -> Num {
+        match self.lot_size(market) {
+            Some(lot_size) => floor_to_step(qty, lot_size.min_qty, lot_size.step_size),
+            None => qty,
+        }
+    }
+
+    /// Returns the order with its prices rounded to the tick size and its quantity
+    /// rounded down to the step size.
+    pub fn round_order(&self, order: NewOrder) -> NewOrder {
+        let market = is_market(order.order_type());
+        let mut order = order;
+
+        if let Some(qty) = order.quantity() {
+            order = order.set_quantity(self.round_qty(qty, market));
+        }
+        if let Some(price) = order.price() {
+            order = order.set_price(self.round_price(price));
+        }
+        if let Some(stop_price) = order.stop_price() {
+            order = order.set_stop_price(self.round_price(stop_price));
+        }
+        if let Some(activation_price) = order.activation_price() {
+            order = order.set_activation_price(self.round_price(activation_price));
+        }
+
+        order
+    }
+
+    pub fn check_price(&self, price: Num) -> Result<()> {
+        let filter = match self.price_filter {
+            Some(ref filter) => filter,
+            None => return Ok(()),
+        };
+        let zero = Num::default();
+
+        if filter.min_price > zero && price < filter.min_price {
+            return self.invalid(format!(
+                "price {} is below the minimum price {}",
+                price, filter.min_price
+            ));
+        }
+        if filter.max_price > zero && price > filter.max_price {
+            return self.invalid(format!(
+                "price {} is above the maximum price {}",
+                price, filter.max_price
+            ));
+        }
+        if !is_multiple(price, filter.min_price, filter.tick_size) {
+            return self.invalid(format!(
+                "price {} is not a multiple of the tick size {}",
+                price, filter.tick_size
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn check_qty(&self, qty: Num, market: bool) -> Result<()> {
+        let lot_size = match self.lot_size(market) {
+            Some(lot_size) => lot_size,
+            None => return Ok(()),
+        };
+        let zero = Num::default();
+
+        if qty < lot_size.min_qty {
+            return self.invalid(format!(
+                "quantity {} is below the minimum quantity {}",
+                qty, lot_size.min_qty
+            ));
+        }
+        if lot_size.max_qty > zero && qty > lot_size.max_qty {
+            return self.invalid(format!(
+                "quantity {} is above the maximum quantity {}",
+                qty, lot_size.max_qty
+            ));
+        }
+        if !is_multiple(qty, lot_size.min_qty, lot_size.step_size) {
+            return self.invalid(format!(
+                "quantity {} is not a multiple of the step size {}",
+                qty, lot_size.step_size
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn check_notional(&self, price: Num, qty: Num) -> Result<()> {
+        if let Some(min_notional) = self.min_notional {
+            let notional = price * qty;
+            if notional < min_notional {
+                return self.invalid(format!(
+                    "notional {} is below the minimum notional {}",
+                    notional, min_notional
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks the order against the filters, without sending it.
+    ///
+    /// The notional is checked with the limit price, or else the stop or activation price;
+    /// it can't be checked locally for MARKET orders. PERCENT_PRICE, which depends on the
+    /// mark price, is left to the exchange.
+    pub fn validate(&self, order: &NewOrder) -> Result<()> {
+        if order.symbol().to_uppercase() != self.symbol {
+            return self.invalid(format!("rules don't apply to {}", order.symbol()));
+        }
+
+        for price in [order.price(), order.stop_price(), order.activation_price()]
+            .iter()
+            .flatten()
+        {
+            self.check_price(*price)?;
+        }
+
+        let qty = match order.quantity() {
+            Some(qty) => qty,
+            None if order.close_position() => return Ok(()),
+            None => return self.invalid("quantity is required unless closing the position".into()),
+        };
+        self.check_qty(qty, is_market(order.order_type()))?;
+
+        if let Some(price) = order
+            .price()
+            .or_else(|| order.stop_price())
+            .or_else(|| order.activation_price())
+        {
+            self.check_notional(price, qty)?;
+        }
+
+        Ok(())
+    }
+
+    fn lot_size(&self, market: bool) -> Option<&LotSize> {
+        if market {
+            self.market_lot_size.as_ref().or(self.lot_size.as_ref())
+        } else {
+            self.lot_size.as_ref()
+        }
+    }
+
+    fn invalid(&self, reason: String) -> Result<()> {
+        Err(ErrorKind::InvalidOrder(self.symbol.clone(), reason).into())
+    }
+}
+
+fn lot_size(min_qty: &NumString, max_qty: &NumString, step_size: &NumString) -> Option<LotSize> {
+    Some(LotSize {
+        min_qty: num(min_qty)?,
+        max_qty: num(max_qty)?,
+        step_size: num(step_size)?,
+    })
+}
+
+// The filters are strings, unless parsed by the `decimal` feature
+#[cfg(not(feature = "decimal"))]
+fn num(value: &NumString) -> Option<Num> {
+    value.parse().ok()
+}
+
+#[cfg(feature = "decimal")]
+fn num(value: &NumString) -> Option<Num> {
+    Some(*value)
+}
+
+fn is_market(order_type: &OrderType) -> bool {
+    matches!(
+        *order_type,
+        OrderType::Market
+            | OrderType::StopMarket
+            | OrderType::TakeProfitMarket
+            | OrderType::TrailingStopMarket
+    )
+}
+
+// Binance counts the steps from the minimum (`origin`): (price - minPrice) % tickSize == 0
+
+#[cfg(not(feature = "decimal"))]
+fn floor_to_step(value: f64, origin: f64, step: f64) -> f64 {
+    if step <= 0.0 {
+        return value;
+    }
+    // Tolerate the float error of divisions like 0.3 / 0.1
+    let steps = ((value - origin) / step + 1e-9).floor();
+    round_to_decimals_of(origin + steps * step, origin, step)
+}
+
+#[cfg(not(feature = "decimal"))]
+fn round_to_step(value: f64, origin: f64, step: f64) -> f64 {
+    if step <= 0.0 {
+        return value;
+    }
+    let steps = ((value - origin) / step).round();
+    round_to_decimals_of(origin + steps * step, origin, step)
+}
+
+#[cfg(not(feature = "decimal"))]
+fn is_multiple(value: f64, origin: f64, step: f64) -> bool {
+    if step <= 0.0 {
+        return true;
+    }
+    let steps = (value - origin) / step;
+    (steps - steps.round()).abs() < 1e-6
+}
+
+// Drops the float noise of `origin + steps * step`, e.g. 0.30000000000000004 with a step of 0.1
+#[cfg(not(feature = "decimal"))]
+fn round_to_decimals_of(value: f64, origin: f64, step: f64) -> f64 {
+    let decimals = decimals(origin).max(decimals(step));
+    let factor = 10f64.powi(decimals as i32);
+
+    (value * factor).round() / factor
+}
+
+#[cfg(not(feature = "decimal"))]
+fn decimals(value: f64) -> usize {
+    let value = value.to_string();
+    value.find('.').map_or(0, |dot| value.len() - dot - 1)
+}
+
+#[cfg(feature = "decimal")]
+fn floor_to_step(value: Num, origin: Num, step: Num) -> Num {
+    if step <= Num::default() {
+        return value;
+    }
+    (origin + ((value - origin) / step).floor() * step).round_dp(origin.scale().max(step.scale()))
+}
+
+#[cfg(feature = "decimal")]
+fn round_to_step(value: Num, origin: Num, step: Num) -> Num {
+    if step <= Num::default() {
+        return value;
+    }
+    // Half away from zero like `f64::round`, `Decimal::round` rounds half to even
+    let steps = ((value - origin) / step).round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero);
+    (origin + steps * step).round_dp(origin.scale().max(step.scale()))
+}
+
+#[cfg(feature = "decimal")]
+fn is_multiple(value: Num, origin: Num, step: Num) -> bool {
+    step <= Num::default() || ((value - origin) % step).is_zero()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::OrderSide;
+
+    fn num(value: &str) -> Num {
+        value.parse().unwrap()
+    }
+
+    fn rules() -> SymbolRules {
+        let filters: Vec<Filters> = serde_json::from_str(
+            r#"[
+                {"filterType": "PRICE_FILTER", "minPrice": "0.05", "maxPrice": "100000", "tickSize": "0.10"},
+                {"filterType": "LOT_SIZE", "minQty": "0.001", "maxQty": "1000", "stepSize": "0.001"},
+                {"filterType": "MARKET_LOT_SIZE", "minQty": "0.1", "maxQty": "100", "stepSize": "0.1"},
+                {"filterType": "MIN_NOTIONAL", "minNotional": "5", "applyToMarket": true, "avgPriceMins": 5}
+            ]"#,
+        )
+        .unwrap();
+
+        SymbolRules::new("BTCUSDT", &filters)
+    }
+
+    #[test]
+    fn filters_parsed() {
+        let rules = rules();
+
+        let price_filter = rules.price_filter.as_ref().unwrap();
+        assert_eq!(price_filter.min_price, num("0.05"));
+        assert_eq!(price_filter.tick_size, num("0.1"));
+        assert_eq!(rules.lot_size.as_ref().unwrap().step_size, num("0.001"));
+        assert_eq!(rules.market_lot_size.as_ref().unwrap().min_qty, num("0.1"));
+        assert_eq!(rules.min_notional, Some(num("5")));
+    }
+
+    #[test]
+    fn price_rounded_to_the_nearest_tick_from_the_minimum_price() {
+        let rules = rules();
+
+        assert_eq!(rules.round_price(num("1.23")), num("1.25"));
+        assert_eq!(rules.round_price(num("1.29")), num("1.25"));
+        assert_eq!(rules.round_price(num("1.31")), num("1.35"));
+        // Half away from zero
+        assert_eq!(rules.round_price(num("1.30")), num("1.35"));
+    }
+
+    #[test]
+    fn rounded_prices_pass_the_check() {
+        let rules = rules();
+
+        for price in &["0.05", "1.23", "30000.123", "99999.97", "0.3"] {
+            let rounded = rules.round_price(num(price));
+            assert!(rules.check_price(rounded).is_ok(), "{} rounded to {}", price, rounded);
+        }
+        assert!(rules.check_price(num("1.2")).is_err());
+        assert!(rules.check_price(num("0.01")).is_err());
+        assert!(rules.check_price(num("100000.05")).is_err());
+    }
+
+    #[test]
+    fn qty_rounded_down_to_the_step() {
+        let rules = rules();
+
+        assert_eq!(rules.round_qty(num("0.0129"), false), num("0.012"));
+        assert_eq!(rules.round_qty(num("1.9999"), false), num("1.999"));
+        // MARKET_LOT_SIZE for market orders
+        assert_eq!(rules.round_qty(num("0.19"), true), num("0.1"));
+
+        assert!(rules.check_qty(num("0.012"), false).is_ok());
+        assert!(rules.check_qty(num("0.0125"), false).is_err());
+        assert!(rules.check_qty(num("0.0005"), false).is_err());
+        assert!(rules.check_qty(num("1001"), false).is_err());
+        assert!(rules.check_qty(num("0.05"), true).is_err());
+    }
+
+    #[test]
+    fn float_error_tolerated() {
+        let rules = rules();
+        // 0.30000000000000004 with f64
+        let qty = num("0.1") + num("0.2");
+
+        assert_eq!(rules.round_qty(qty, true), num("0.3"));
+        assert!(rules.check_qty(qty, true).is_ok());
+        // 0.3 / 0.1 is 2.9999999999999996 with f64
+        assert_eq!(rules.round_qty(num("0.3"), true), num("0.3"));
+        assert_eq!(rules.round_price(num("0.25") + num("0.1")), num("0.35"));
+        assert!(rules.check_price(num("0.25") + num("0.1")).is_ok());
+    }
+
+    #[test]
+    fn min_notional() {
+        let rules = rules();
+
+        assert!(rules.check_notional(num("100"), num("0.04")).is_err());
+        assert!(rules.check_notional(num("100"), num("0.05")).is_ok());
+    }
+
+    #[test]
+    fn validate_and_round_order() {
+        let rules = rules();
+        let order = NewOrder::limit("btcusdt", OrderSide::Buy, num("0.0501"), num("30000.123"));
+
+        match rules.validate(&order) {
+            Err(Error(ErrorKind::InvalidOrder(symbol, _), _)) => assert_eq!(symbol, "BTCUSDT"),
+            _ => panic!("expected InvalidOrder"),
+        }
+
+        let order = rules.round_order(order);
+        assert_eq!(order.quantity(), Some(num("0.05")));
+        assert_eq!(order.price(), Some(num("30000.15")));
+        assert!(rules.validate(&order).is_ok());
+
+        // Notional of 3 below 5
+        let order = NewOrder::limit("BTCUSDT", OrderSide::Buy, num("0.001"), num("3000.05"));
+        assert!(rules.validate(&order).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+use crate::errors::*;
+use crate::futures::general::FuturesGeneral;
+use crate::util::get_timestamp;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// Delay before measuring again after a failed synchronization
+const RETRY_DELAY: Duration = Duration::from_secs(10);
+
+#[derive(Default)]
+struct State {
+    // Server time minus local time, in milliseconds
+    offset: i64,
+    round_trip: Option<Duration>,
+    last_sync: Option<Instant>,
+    last_failure: Option<Instant>,
+    stale: bool,
+    // A request is measuring the offset, the others keep the current one meanwhile
+    syncing: bool,
+}
+
+/// Offset between the local clock and the exchange clock, enabled by `Config::set_time_sync`.
+///
+/// Refreshed before a signed request once `resync_interval` elapsed, and after a -1021
+/// (timestamp outside of the recvWindow) rejection.
+#[derive(Clone)]
+pub struct TimeSync {
+    state: Arc<Mutex<State>>,
+    resync_interval: Duration,
+}
+
+impl TimeSync {
+    pub fn new(resync_interval: Duration) -> Self {
+        TimeSync {
+            state: Arc::new(Mutex::new(State::default())),
+            resync_interval,
+        }
+    }
+
+    /// Measures the offset with `FuturesGeneral::get_server_time`.
+    pub fn sync(&self, general: &FuturesGeneral) -> Result<()> {
+        let sent = get_timestamp()?;
+        let started = Instant::now();
+        match general.get_server_time() {
+            Ok(server_time) => self.update(sent, started.elapsed(), server_time.server_time),
+            Err(e) => return Err(self.failed(e)),
+        }
+
+        Ok(())
+    }
+
+    /// Async version of `sync`.
+    #[cfg(feature = "async")]
+    pub async fn sync_async(
+        &self, general: &crate::asynchronous::futures::general::FuturesGeneral,
+    ) -> Result<()> {
+        let sent = get_timestamp()?;
+        let started = Instant::now();
+        match general.get_server_time().await {
+            Ok(server_time) => self.update(sent, started.elapsed(), server_time.server_time),
+            Err(e) => return Err(self.failed(e)),
+        }
+
+        Ok(())
+    }
+
+    /// Server time minus local time, in milliseconds.
+    pub fn offset(&self) -> i64 {
+        self.state.lock().unwrap().offset
+    }
+
+    /// Round-trip time of the last synchronization.
+    pub fn round_trip(&self) -> Option<Duration> {
+        self.state.lock().unwrap().round_trip
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.state.lock().unwrap().last_sync.is_some()
+    }
+
+    /// Current server time estimate, in milliseconds.
+    pub fn timestamp(&self) -> Result<u64> {
+        let local = get_timestamp()? as i64;
+
+        Ok((local + self.offset()).max(0) as u64)
+    }
+
+    // Returns a guard if the offset must be measured and no other request is measuring it,
+    // the measure is then retried at most every `RETRY_DELAY` while it fails
+    pub(crate) fn try_begin_sync(&self) -> Option<SyncGuard<'_>> {
+        let mut state = self.state.lock().unwrap();
+        if state.syncing {
+            return None;
+        }
+        if let Some(last_failure) = state.last_failure {
+            if last_failure.elapsed() < RETRY_DELAY.min(self.resync_interval) {
+                return None;
+            }
+        }
+        let needs_sync = match state.last_sync {
+            Some(last_sync) => state.stale || last_sync.elapsed() >= self.resync_interval,
+            None => true,
+        };
+        if !needs_sync {
+            return None;
+        }
+        state.syncing = true;
+
+        Some(SyncGuard { time_sync: self })
+    }
+
+    // Forces a resync before the next signed request, keeping the current offset until then
+    pub(crate) fn invalidate(&self) {
+        self.state.lock().unwrap().stale = true;
+    }
+
+    fn update(&self, sent: u64, round_trip: Duration, server_time: u64) {
+        // The server stamped its time about half way through the round trip
+        let local = sent as i64 + round_trip.as_millis() as i64 / 2;
+
+        let mut state = self.state.lock().unwrap();
+        state.offset = server_time as i64 - local;
+        state.round_trip = Some(round_trip);
+        state.last_sync = Some(Instant::now());
+        state.last_failure = None;
+        state.stale = false;
+    }
+
+    fn failed(&self, error: Error) -> Error {
+        self.state.lock().unwrap().last_failure = Some(Instant::now());
+        error
+    }
+}
+
+// Ends the synchronization started by `try_begin_sync`, even if the request was dropped
+pub(crate) struct SyncGuard<'a> {
+    time_sync: &'a TimeSync,
+}
+
+impl<'a> Drop for SyncGuard<'a> {
+    fn drop(&mut self) {
+        self.time_sync.state.lock().unwrap().syncing = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offset_corrected_by_half_the_round_trip() {
+        let time_sync = TimeSync::new(Duration::from_secs(60));
+        time_sync.update(1_000_000, Duration::from_millis(100), 1_002_000);
+
+        assert_eq!(time_sync.offset(), 1_950);
+        assert_eq!(time_sync.round_trip(), Some(Duration::from_millis(100)));
+        assert!(time_sync.is_synced());
+    }
+
+    #[test]
+    fn negative_offset_when_the_local_clock_is_ahead() {
+        let time_sync = TimeSync::new(Duration::from_secs(60));
+        time_sync.update(1_000_000, Duration::from_millis(30), 999_000);
+
+        assert_eq!(time_sync.offset(), -1_015);
+    }
+
+    #[test]
+    fn timestamp_applies_the_offset() {
+        let time_sync = TimeSync::new(Duration::from_secs(60));
+        let local = get_timestamp().unwrap();
+        time_sync.update(local, Duration::from_millis(0), local + 5_000);
+
+        let timestamp = time_sync.timestamp().unwrap();
+        assert!(timestamp >= local + 5_000 && timestamp < local + 6_000);
+    }
+
+    #[test]
+    fn one_sync_at_a_time() {
+        let time_sync = TimeSync::new(Duration::from_secs(60));
+
+        let guard = time_sync.try_begin_sync();
+        assert!(guard.is_some());
+        assert!(time_sync.try_begin_sync().is_none());
+
+        drop(guard);
+        assert!(time_sync.try_begin_sync().is_some());
+    }
+
+    #[test]
+    fn no_sync_until_the_interval_elapsed_or_invalidated() {
+        let time_sync = TimeSync::new(Duration::from_secs(60));
+        time_sync.update(1_000_000, Duration::from_millis(10), 1_000_000);
+        assert!(time_sync.try_begin_sync().is_none());
+
+        time_sync.invalidate();
+        assert!(time_sync.try_begin_sync().is_some());
+    }
+
+    #[test]
+    fn failed_sync_is_not_retried_immediately() {
+        let time_sync = TimeSync::new(Duration::from_secs(60));
+        time_sync.update(1_000_000, Duration::from_millis(10), 1_000_000);
+        time_sync.invalidate();
+
+        let guard = time_sync.try_begin_sync();
+        let _ = time_sync.failed("no response".into());
+        drop(guard);
+
+        // Still stale, but the previous offset is kept until RETRY_DELAY elapsed
+        assert!(time_sync.try_begin_sync().is_none());
+        assert_eq!(time_sync.offset(), -5);
+    }
+}
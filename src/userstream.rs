@@ -1,9 +1,15 @@
 use crate::model::*;
 use crate::client::*;
+use crate::config::*;
 use crate::errors::*;
+use crate::websockets::{ShutdownHandle, WebSockets, WebsocketEvent};
 use serde_json::from_str;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
-static USER_DATA_STREAM: &str = "/fapi/v1/listenKey";
+pub(crate) static USER_DATA_STREAM: &str = "/fapi/v1/listenKey";
 
 #[derive(Clone)]
 pub struct UserStream {
@@ -37,3 +43,237 @@ impl UserStream {
         Ok(success)
     }
 }
+
+// Binance closes a listen key without keepalive for 60 minutes
+static KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+/// User data stream which manages its listen key: the key is created with the
+/// connection, renewed every 30 minutes from a background thread, recreated with
+/// a new connection when it expired or the connection was lost, and closed on drop.
+///
+/// Reconnections use the backoff of `Config::ws_reconnect`, or the default `RetryPolicy`.
+///
+/// Closing the key on drop is a blocking HTTP request, like `close`. Call `close`
+/// beforehand to handle its error, or disable it with `set_close_on_drop(false)`, e.g.
+/// when dropped from an async task.
+pub struct ManagedUserStream<'a> {
+    user_stream: UserStream,
+    web_socket: WebSockets<'a>,
+    listen_key: Arc<Mutex<Option<String>>>,
+    keep_alive_interval: Duration,
+    // Stops the keepalive thread
+    stopped: Option<Arc<AtomicBool>>,
+    // Set when the handler failed, its error ends `event_loop`
+    handler_failed: Arc<AtomicBool>,
+    // Set when the listen key expired or the connection was lost
+    restart_needed: Arc<AtomicBool>,
+    reconnect_policy: RetryPolicy,
+    shutdown: ShutdownHandle,
+    close_on_drop: bool,
+}
+
+impl<'a> ManagedUserStream<'a> {
+    pub fn new<Callback>(user_stream: UserStream, handler: Callback) -> ManagedUserStream<'a>
+    where
+        Callback: FnMut(WebsocketEvent) -> Result<()> + 'a,
+    {
+        Self::new_with_config(user_stream, handler, Config::default())
+    }
+
+    pub fn new_with_config<Callback>(
+        user_stream: UserStream, mut handler: Callback, config: Config,
+    ) -> ManagedUserStream<'a>
+    where
+        Callback: FnMut(WebsocketEvent) -> Result<()> + 'a,
+    {
+        let handler_failed = Arc::new(AtomicBool::new(false));
+        let failed = handler_failed.clone();
+        let restart_needed = Arc::new(AtomicBool::new(false));
+        let restart = restart_needed.clone();
+        let reconnect_policy = config.ws_reconnect.clone().unwrap_or_default();
+        // Connection losses are handled here, with a new listen key
+        let config = Config {
+            ws_reconnect: None,
+            ..config
+        };
+
+        let web_socket = WebSockets::new_with_config(
+            move |event: WebsocketEvent| {
+                let expired = matches!(event, WebsocketEvent::ListenKeyExpired(_));
+                if matches!(event, WebsocketEvent::Disconnected(_)) {
+                    restart.store(true, Ordering::Relaxed);
+                }
+                if let Err(e) = handler(event) {
+                    failed.store(true, Ordering::Relaxed);
+                    return Err(e);
+                }
+                if expired {
+                    restart.store(true, Ordering::Relaxed);
+                    bail!("Listen key expired");
+                }
+                Ok(())
+            },
+            config,
+        );
+
+        ManagedUserStream {
+            user_stream,
+            shutdown: web_socket.shutdown_handle(),
+            web_socket,
+            listen_key: Arc::new(Mutex::new(None)),
+            keep_alive_interval: KEEP_ALIVE_INTERVAL,
+            stopped: None,
+            handler_failed,
+            restart_needed,
+            reconnect_policy,
+            close_on_drop: true,
+        }
+    }
+
+    pub fn set_keep_alive_interval(mut self, keep_alive_interval: Duration) -> Self {
+        self.keep_alive_interval = keep_alive_interval;
+        self
+    }
+
+    /// Whether the listen key is closed on drop, true by default. The key is renewed
+    /// no more after the drop either way.
+    pub fn set_close_on_drop(mut self, close_on_drop: bool) -> Self {
+        self.close_on_drop = close_on_drop;
+        self
+    }
+
+    /// Listen key of the current connection.
+    pub fn listen_key(&self) -> Option<String> {
+        self.listen_key.lock().unwrap().clone()
+    }
+
+    /// Handle stopping `event_loop` from another thread, see `WebSockets::shutdown_handle`.
+    /// The listen key is still closed by `close` or on drop.
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown.clone()
+    }
+
+    /// Creates the listen key, connects, and starts renewing the key.
+    pub fn start(&mut self) -> Result<()> {
+        self.open()?;
+
+        if self.stopped.is_none() {
+            let stopped = Arc::new(AtomicBool::new(false));
+            spawn_keep_alive(
+                self.user_stream.clone(),
+                self.listen_key.clone(),
+                self.keep_alive_interval,
+                stopped.clone(),
+            );
+            self.stopped = Some(stopped);
+        }
+
+        Ok(())
+    }
+
+    /// Passes the events to the handler until `running` is false, or the handler
+    /// or a reconnection failed. Only an expired listen key or a lost connection
+    /// restart the stream, any other error is returned.
+    pub fn event_loop(&mut self, running: &AtomicBool) -> Result<()> {
+        while running.load(Ordering::Relaxed) && !self.shutdown.is_shutdown() {
+            if let Err(e) = self.web_socket.event_loop(running) {
+                if self.shutdown.is_shutdown() {
+                    break;
+                }
+                if self.handler_failed.swap(false, Ordering::Relaxed)
+                    || !self.restart_needed.swap(false, Ordering::Relaxed)
+                {
+                    return Err(e);
+                }
+                self.restart(running)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stops renewing the listen key, disconnects and closes the key.
+    pub fn close(&mut self) -> Result<()> {
+        if let Some(stopped) = self.stopped.take() {
+            stopped.store(true, Ordering::Relaxed);
+        }
+        let listen_key = match self.listen_key.lock().unwrap().take() {
+            Some(listen_key) => listen_key,
+            None => return Ok(()),
+        };
+        let _ = self.web_socket.disconnect();
+        self.user_stream.close(&listen_key)?;
+
+        Ok(())
+    }
+
+    fn open(&mut self) -> Result<()> {
+        let listen_key = self.user_stream.start()?.listen_key;
+        *self.listen_key.lock().unwrap() = Some(listen_key.clone());
+
+        self.web_socket.connect(&listen_key)
+    }
+
+    fn restart(&mut self, running: &AtomicBool) -> Result<()> {
+        if let Some(listen_key) = self.listen_key.lock().unwrap().take() {
+            // Already gone if it expired
+            let _ = self.user_stream.close(&listen_key);
+        }
+
+        let mut attempt = 0;
+        while running.load(Ordering::Relaxed) && !self.shutdown.is_shutdown() {
+            thread::sleep(self.reconnect_policy.backoff(attempt));
+
+            match self.open() {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    if self.handler_failed.swap(false, Ordering::Relaxed) {
+                        return Err(e);
+                    }
+                    attempt += 1;
+                    if attempt > self.reconnect_policy.max_retries {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Drop for ManagedUserStream<'a> {
+    // Blocks on the HTTP request closing the listen key, unless `close` was called
+    fn drop(&mut self) {
+        if self.close_on_drop {
+            let _ = self.close();
+        } else if let Some(stopped) = self.stopped.take() {
+            stopped.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+// Renews the current listen key every `interval`, a failed renewal is retried a minute later
+fn spawn_keep_alive(
+    user_stream: UserStream, listen_key: Arc<Mutex<Option<String>>>, interval: Duration,
+    stopped: Arc<AtomicBool>,
+) {
+    thread::spawn(move || {
+        let retry_interval = interval.min(Duration::from_secs(60));
+        let mut next_keep_alive = Instant::now() + interval;
+
+        while !stopped.load(Ordering::Relaxed) {
+            thread::sleep(Duration::from_secs(1));
+            if Instant::now() < next_keep_alive {
+                continue;
+            }
+
+            let current_key = listen_key.lock().unwrap().clone();
+            next_keep_alive = match current_key {
+                Some(current_key) if user_stream.keep_alive(&current_key).is_err() => {
+                    Instant::now() + retry_interval
+                }
+                _ => Instant::now() + interval,
+            };
+        }
+    });
+}
@@ -1,8 +1,12 @@
 use crate::errors::*;
+use crate::model::{KlineInterval, KlineSummaries, KlineSummary, Num};
 use std::collections::BTreeMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 use serde_json::Value;
 
+pub(crate) static NEW_ORDER_RESP_TYPE_RESULT: &str = "RESULT";
+// static NEW_ORDER_RESP_TYPE_ACK: &str = "ACK";
+
 pub fn build_request(parameters: &BTreeMap<String, String>) -> String {
     let mut request = String::new();
     for (key, value) in parameters {
@@ -14,6 +18,8 @@ pub fn build_request(parameters: &BTreeMap<String, String>) -> String {
     request
 }
 
+// The timestamp and the signature are added by the client each time the
+// request is sent, so a retried request is not rejected as too old.
 pub fn build_signed_request(
     mut parameters: BTreeMap<String, String>, recv_window: u64,
 ) -> Result<String> {
@@ -21,31 +27,141 @@ pub fn build_signed_request(
         parameters.insert("recvWindow".into(), recv_window.to_string());
     }
 
-    if let Ok(timestamp) = get_timestamp() {
-        parameters.insert("timestamp".into(), timestamp.to_string());
+    Ok(build_request(&parameters))
+}
+
+// Parameters of the requests for ONE symbol
+pub(crate) fn symbol_parameters(symbol: String) -> BTreeMap<String, String> {
+    let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+    parameters.insert("symbol".into(), symbol);
+
+    parameters
+}
+
+// Builds the query of the public requests for ONE symbol
+pub(crate) fn build_symbol_request(symbol: String) -> String {
+    build_request(&symbol_parameters(symbol))
+}
+
+// Parameters of the requests for ONE order, by its id
+pub(crate) fn order_id_parameters(symbol: String, order_id: u64) -> BTreeMap<String, String> {
+    let mut parameters = symbol_parameters(symbol);
+    parameters.insert("orderId".into(), order_id.to_string());
+
+    parameters
+}
+
+// Parameters of the requests for ONE order, by its client order id
+pub(crate) fn client_order_id_parameters(
+    symbol: String, orig_client_order_id: u64,
+) -> BTreeMap<String, String> {
+    let mut parameters = symbol_parameters(symbol);
+    parameters.insert("origClientOrderId".into(), orig_client_order_id.to_string());
 
-        let mut request = String::new();
-        for (key, value) in &parameters {
-            let param = format!("{}={}&", key, value);
-            request.push_str(param.as_ref());
-        }
-        request.pop(); // remove last &
+    parameters
+}
 
-        Ok(request)
-    } else {
-        bail!("Failed to get timestamp")
+// Parameters of the income history request
+pub(crate) fn income_parameters(symbol: String, start_time: u64) -> BTreeMap<String, String> {
+    let mut parameters = symbol_parameters(symbol);
+    parameters.insert("startTime".into(), start_time.to_string());
+
+    parameters
+}
+
+// Parameters of the futures historical trades request
+pub(crate) fn historical_trades_parameters(
+    symbol: String, from_id: Option<u64>, limit: Option<u16>,
+) -> BTreeMap<String, String> {
+    let mut parameters = symbol_parameters(symbol);
+
+    // Add two optional parameters
+    if let Some(lt) = limit {
+        parameters.insert("limit".into(), format!("{}", lt));
     }
+    if let Some(fi) = from_id {
+        parameters.insert("fromId".into(), format!("{}", fi));
+    }
+
+    parameters
+}
+
+// Builds the query of the futures aggregated trades request
+pub(crate) fn build_agg_trades_request(
+    symbol: String, from_id: Option<u64>, start_time: Option<u64>, end_time: Option<u64>,
+    limit: Option<u16>,
+) -> String {
+    let mut parameters = symbol_parameters(symbol);
+
+    // Add four optional parameters
+    if let Some(lt) = limit {
+        parameters.insert("limit".into(), format!("{}", lt));
+    }
+    if let Some(st) = start_time {
+        parameters.insert("startTime".into(), format!("{}", st));
+    }
+    if let Some(et) = end_time {
+        parameters.insert("endTime".into(), format!("{}", et));
+    }
+    if let Some(fi) = from_id {
+        parameters.insert("fromId".into(), format!("{}", fi));
+    }
+
+    build_request(&parameters)
+}
+
+// Builds the query shared by the spot and futures kline endpoints
+pub(crate) fn build_klines_request(
+    symbol: String, interval: KlineInterval, limit: Option<u16>, start_time: Option<u64>,
+    end_time: Option<u64>,
+) -> String {
+    let mut parameters = symbol_parameters(symbol);
+    parameters.insert("interval".into(), interval.to_string());
+
+    // Add three optional parameters
+    if let Some(lt) = limit {
+        parameters.insert("limit".into(), format!("{}", lt));
+    }
+    if let Some(st) = start_time {
+        parameters.insert("startTime".into(), format!("{}", st));
+    }
+    if let Some(et) = end_time {
+        parameters.insert("endTime".into(), format!("{}", et));
+    }
+
+    build_request(&parameters)
+}
+
+pub(crate) fn to_kline_summaries(parsed_data: &[Vec<Value>]) -> KlineSummaries {
+    KlineSummaries::AllKlineSummaries(
+        parsed_data
+            .iter()
+            .map(|row| KlineSummary {
+                open_time: to_i64(&row[0]),
+                open: to_num(&row[1]),
+                high: to_num(&row[2]),
+                low: to_num(&row[3]),
+                close: to_num(&row[4]),
+                volume: to_num(&row[5]),
+                close_time: to_i64(&row[6]),
+                quote_asset_volume: to_num(&row[7]),
+                number_of_trades: to_i64(&row[8]),
+                taker_buy_base_asset_volume: to_num(&row[9]),
+                taker_buy_quote_asset_volume: to_num(&row[10]),
+            })
+            .collect(),
+    )
 }
 
 pub fn to_i64(v: &Value) -> i64 {
     v.as_i64().unwrap()
 }
 
-pub fn to_f64(v: &Value) -> f64 {
+pub fn to_num(v: &Value) -> Num {
     v.as_str().unwrap().parse().unwrap()
 }
 
-fn get_timestamp() -> Result<u64> {
+pub(crate) fn get_timestamp() -> Result<u64> {
     let start = SystemTime::now();
     let since_epoch = start.duration_since(UNIX_EPOCH)?;
 
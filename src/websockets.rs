@@ -1,28 +1,57 @@
 use crate::model::*;
+use crate::config::*;
 use crate::errors::*;
+use crate::streams::StreamName;
 use url::Url;
-use serde_json::from_value;
+use serde::de::DeserializeOwned;
+use serde_json::json;
 
+use std::collections::HashMap;
+use std::io;
+use std::net::{Shutdown, TcpStream};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 use tungstenite::{connect, Message};
 use tungstenite::protocol::WebSocket;
 use tungstenite::client::AutoStream;
 use tungstenite::handshake::client::Response;
+use tungstenite::stream::Stream as StreamSwitcher;
 
-static WEBSOCKET_URL: &str = "wss://fstream.binance.com/stream?streams=";
+static WEBSOCKET_COMBINED_STREAM: &str = "/stream?streams=";
 
-static OUTBOUND_ACCOUNT_INFO: &str = "outboundAccountInfo";
-static EXECUTION_REPORT: &str = "executionReport";
+// Live subscription methods
+pub(crate) const SUBSCRIBE: &str = "SUBSCRIBE";
+pub(crate) const UNSUBSCRIBE: &str = "UNSUBSCRIBE";
+pub(crate) const LIST_SUBSCRIPTIONS: &str = "LIST_SUBSCRIPTIONS";
 
-static KLINE: &str = "kline";
-static AGGREGATED_TRADE: &str = "aggTrade";
-static DEPTH_ORDERBOOK: &str = "depthUpdate";
-static PARTIAL_ORDERBOOK: &str = "lastUpdateId";
+// Event types, from the `e` field of the stream data
+const OUTBOUND_ACCOUNT_INFO: &str = "outboundAccountInfo";
+const EXECUTION_REPORT: &str = "executionReport";
 
-static DAYTICKER: &str = "24hrTicker";
+const KLINE: &str = "kline";
+const AGGREGATED_TRADE: &str = "aggTrade";
+const DEPTH_ORDERBOOK: &str = "depthUpdate";
+const PARTIAL_ORDERBOOK: &str = "lastUpdateId";
+const BOOK_TICKER: &str = "bookTicker";
+const MARK_PRICE_UPDATE: &str = "markPriceUpdate";
 
-static ACCOUNT_UPDATE: &str = "ACCOUNT_UPDATE";
-static ORDER_TRADE_UPDATE: &str = "ORDER_TRADE_UPDATE";
+const DAYTICKER: &str = "24hrTicker";
+
+const MINI_TICKER: &str = "24hrMiniTicker";
+const CONTINUOUS_KLINE: &str = "continuous_kline";
+const FORCE_ORDER: &str = "forceOrder";
+const COMPOSITE_INDEX: &str = "compositeIndex";
+
+const ACCOUNT_UPDATE: &str = "ACCOUNT_UPDATE";
+const ORDER_TRADE_UPDATE: &str = "ORDER_TRADE_UPDATE";
+const MARGIN_CALL: &str = "MARGIN_CALL";
+const ACCOUNT_CONFIG_UPDATE: &str = "ACCOUNT_CONFIG_UPDATE";
+const LISTEN_KEY_EXPIRED: &str = "listenKeyExpired";
+
+// Stream names, for the data without event type
+const BOOK_TICKER_STREAM: &str = "@bookTicker";
 
 #[allow(clippy::large_enum_variant)]
 pub enum WebsocketEvent {
@@ -37,32 +66,393 @@ pub enum WebsocketEvent {
     FuturesAccountUpdateEvent(FuturesAccountUpdateEvent),
     OrderTradeUpdateEvent(OrderTradeUpdateEvent),
     FuturesFunding(FuturesFunding),
+    /// Mark prices of every symbol, from `!markPrice@arr`.
+    MarkPrices(Vec<FuturesFunding>),
+    MiniTicker(Vec<MiniTickerEvent>),
+    ContinuousKline(ContinuousKlineEvent),
+    Liquidation(LiquidationEvent),
+    CompositeIndex(CompositeIndexEvent),
+    MarginCall(MarginCallEvent),
+    AccountConfigUpdate(AccountConfigUpdateEvent),
+    /// The listen key of the user data stream expired, the stream sends no more events.
+    ListenKeyExpired(ListenKeyExpiredEvent),
+    /// The connection was established by `connect`.
+    Connected,
+    /// The connection was lost, with the reason. `event_loop` then reconnects
+    /// if `Config::ws_reconnect` is set.
+    Disconnected(String),
+    /// The connection was established again, with the same streams.
+    Reconnected,
+    /// Streams added by the `subscribe` request with this id.
+    Subscribed { id: u64, streams: Vec<String> },
+    /// Streams removed by the `unsubscribe` request with this id.
+    Unsubscribed { id: u64, streams: Vec<String> },
+    /// Current streams, answer to the `list_subscriptions` request with this id.
+    Subscriptions { id: u64, streams: Vec<String> },
+    /// A live subscription request was rejected. `id` is `None` if the exchange
+    /// couldn't read it.
+    SubscriptionError {
+        id: Option<u64>,
+        streams: Vec<String>,
+        code: i64,
+        msg: String,
+    },
+    /// Stream data of a type this version of the library doesn't decode. A text
+    /// message which is not JSON is passed as a string.
+    Unknown(serde_json::Value),
+    /// Stream data which doesn't match the schema of its type, with the
+    /// `ErrorKind::InvalidEvent` error telling why.
+    Invalid {
+        error: Error,
+        data: serde_json::Value,
+    },
+}
+
+/// Type of the stream data of a `WebsocketEvent`, see `StreamName::event_kind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    AccountUpdate,
+    OrderTrade,
+    Trade,
+    OrderBook,
+    DayTicker,
+    Kline,
+    DepthOrderBook,
+    BookTicker,
+    FuturesAccountUpdate,
+    OrderTradeUpdate,
+    FuturesFunding,
+    MarkPrices,
+    MiniTicker,
+    ContinuousKline,
+    Liquidation,
+    CompositeIndex,
+    MarginCall,
+    AccountConfigUpdate,
+    ListenKeyExpired,
+    Unknown,
+}
+
+impl WebsocketEvent {
+    /// Type of the stream data, `None` for the connection and subscription events.
+    pub fn kind(&self) -> Option<EventKind> {
+        let kind = match *self {
+            WebsocketEvent::AccountUpdate(_) => EventKind::AccountUpdate,
+            WebsocketEvent::OrderTrade(_) => EventKind::OrderTrade,
+            WebsocketEvent::Trade(_) => EventKind::Trade,
+            WebsocketEvent::OrderBook(_) => EventKind::OrderBook,
+            WebsocketEvent::DayTicker(_) => EventKind::DayTicker,
+            WebsocketEvent::Kline(_) => EventKind::Kline,
+            WebsocketEvent::DepthOrderBook(_) => EventKind::DepthOrderBook,
+            WebsocketEvent::BookTicker(_) => EventKind::BookTicker,
+            WebsocketEvent::FuturesAccountUpdateEvent(_) => EventKind::FuturesAccountUpdate,
+            WebsocketEvent::OrderTradeUpdateEvent(_) => EventKind::OrderTradeUpdate,
+            WebsocketEvent::FuturesFunding(_) => EventKind::FuturesFunding,
+            WebsocketEvent::MarkPrices(_) => EventKind::MarkPrices,
+            WebsocketEvent::MiniTicker(_) => EventKind::MiniTicker,
+            WebsocketEvent::ContinuousKline(_) => EventKind::ContinuousKline,
+            WebsocketEvent::Liquidation(_) => EventKind::Liquidation,
+            WebsocketEvent::CompositeIndex(_) => EventKind::CompositeIndex,
+            WebsocketEvent::MarginCall(_) => EventKind::MarginCall,
+            WebsocketEvent::AccountConfigUpdate(_) => EventKind::AccountConfigUpdate,
+            WebsocketEvent::ListenKeyExpired(_) => EventKind::ListenKeyExpired,
+            WebsocketEvent::Unknown(_) | WebsocketEvent::Invalid { .. } => EventKind::Unknown,
+            WebsocketEvent::Connected
+            | WebsocketEvent::Disconnected(_)
+            | WebsocketEvent::Reconnected
+            | WebsocketEvent::Subscribed { .. }
+            | WebsocketEvent::Unsubscribed { .. }
+            | WebsocketEvent::Subscriptions { .. }
+            | WebsocketEvent::SubscriptionError { .. } => return None,
+        };
+
+        Some(kind)
+    }
+}
+
+// Live subscription requests, and the streams of the connection they apply to
+#[derive(Default)]
+pub(crate) struct Subscriptions {
+    streams: Vec<String>,
+    next_id: u64,
+    // Method and streams of the requests waiting for an answer, by id
+    pending: HashMap<u64, (&'static str, Vec<String>)>,
+}
+
+impl Subscriptions {
+    pub(crate) fn new(endpoint: &str) -> Self {
+        Subscriptions {
+            streams: endpoint
+                .split('/')
+                .filter(|stream| !stream.is_empty())
+                .map(String::from)
+                .collect(),
+            ..Subscriptions::default()
+        }
+    }
+
+    pub(crate) fn streams(&self) -> &[String] {
+        &self.streams
+    }
+
+    // Endpoint to reconnect to, with the streams acknowledged by the server. Requests
+    // left unanswered by the lost connection may not have been applied, they are dropped.
+    pub(crate) fn reconnect_endpoint(&mut self) -> String {
+        self.pending.clear();
+
+        self.streams.join("/")
+    }
+
+    // Returns the id and the JSON text of the request
+    pub(crate) fn request(&mut self, method: &'static str, streams: &[&str]) -> (u64, String) {
+        self.next_id += 1;
+        let id = self.next_id;
+        let streams: Vec<String> = streams.iter().map(|stream| stream.to_string()).collect();
+
+        let request = if method == LIST_SUBSCRIPTIONS {
+            json!({ "method": method, "id": id })
+        } else {
+            json!({ "method": method, "params": streams, "id": id })
+        };
+        self.pending.insert(id, (method, streams));
+
+        (id, request.to_string())
+    }
+
+    // Event of a text message: the answer to a request or the stream data. A message
+    // which can't be decoded doesn't end the connection, it is passed on as
+    // `WebsocketEvent::Unknown`, or `Invalid` with the reason.
+    pub(crate) fn decode(&mut self, msg: String) -> Option<WebsocketEvent> {
+        let message: serde_json::Value = match serde_json::from_str(&msg) {
+            Ok(message) => message,
+            Err(_) => return Some(WebsocketEvent::Unknown(serde_json::Value::String(msg))),
+        };
+        if let Some(event) = self.response(&message) {
+            return Some(event);
+        }
+
+        parse_message(message)
+    }
+
+    // Event for the answer to a request, `None` if the message is not an answer
+    pub(crate) fn response(&mut self, message: &serde_json::Value) -> Option<WebsocketEvent> {
+        if message.get("stream").is_some()
+            || (message.get("result").is_none() && message.get("error").is_none())
+        {
+            return None;
+        }
+        let id = message["id"].as_u64();
+        let (method, streams) = match id.and_then(|id| self.pending.remove(&id)) {
+            Some(request) => request,
+            None if message.get("error").is_some() => ("", Vec::new()),
+            None => return Some(WebsocketEvent::Unknown(message.clone())),
+        };
+
+        if let Some(error) = message.get("error") {
+            return Some(WebsocketEvent::SubscriptionError {
+                id,
+                streams,
+                code: error["code"].as_i64().unwrap_or_default(),
+                msg: error["msg"].as_str().unwrap_or_default().to_string(),
+            });
+        }
+
+        let id = id.unwrap_or_default();
+        let event = match method {
+            SUBSCRIBE => {
+                add_streams(&mut self.streams, &streams);
+                WebsocketEvent::Subscribed { id, streams }
+            }
+            UNSUBSCRIBE => {
+                self.streams.retain(|stream| !streams.contains(stream));
+                WebsocketEvent::Unsubscribed { id, streams }
+            }
+            _ => {
+                self.streams = message["result"]
+                    .as_array()
+                    .map(|streams| {
+                        streams
+                            .iter()
+                            .filter_map(|stream| stream.as_str().map(String::from))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                WebsocketEvent::Subscriptions {
+                    id,
+                    streams: self.streams.clone(),
+                }
+            }
+        };
+
+        Some(event)
+    }
+}
+
+fn add_streams(current: &mut Vec<String>, streams: &[String]) {
+    for stream in streams {
+        if !current.contains(stream) {
+            current.push(stream.clone());
+        }
+    }
+}
+
+// Heartbeat state of a connection, see `Config::ws_heartbeat`
+pub(crate) struct Watchdog {
+    heartbeat: Option<Heartbeat>,
+    last_received: Instant,
+    last_ping: Instant,
+}
+
+impl Watchdog {
+    pub(crate) fn new(heartbeat: Option<Heartbeat>) -> Self {
+        Watchdog {
+            heartbeat,
+            last_received: Instant::now(),
+            last_ping: Instant::now(),
+        }
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.last_received = Instant::now();
+        self.last_ping = Instant::now();
+    }
+
+    pub(crate) fn received(&mut self) {
+        self.last_received = Instant::now();
+    }
+
+    // Fails when the connection is stale, true when a ping is due
+    pub(crate) fn check(&mut self) -> Result<bool> {
+        let heartbeat = match self.heartbeat {
+            Some(ref heartbeat) => heartbeat,
+            None => return Ok(false),
+        };
+        let silence = self.last_received.elapsed();
+        if silence >= heartbeat.stale_timeout {
+            return Err(ErrorKind::StaleConnection(silence).into());
+        }
+        if self.last_ping.elapsed() >= heartbeat.ping_interval {
+            self.last_ping = Instant::now();
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    // When `check` has to run again
+    #[cfg(feature = "async")]
+    pub(crate) fn next_check(&self) -> Option<Instant> {
+        self.heartbeat.as_ref().map(|heartbeat| {
+            (self.last_received + heartbeat.stale_timeout).min(self.last_ping + heartbeat.ping_interval)
+        })
+    }
+
+    // Read timeout of the blocking socket, so the watchdog runs while no data arrives
+    fn read_timeout(&self) -> Option<Duration> {
+        self.heartbeat.as_ref().map(|heartbeat| {
+            heartbeat
+                .ping_interval
+                .min(heartbeat.stale_timeout)
+                .min(Duration::from_secs(1))
+                .max(Duration::from_millis(1))
+        })
+    }
+}
+
+/// Stops the `event_loop` of a `WebSockets` from another thread, see
+/// `WebSockets::shutdown_handle`.
+#[derive(Clone, Default)]
+pub struct ShutdownHandle {
+    shutdown: Arc<AtomicBool>,
+    // Clone of the TCP stream of the current connection
+    stream: Arc<Mutex<Option<TcpStream>>>,
+}
+
+impl ShutdownHandle {
+    /// Stops the event loop right away: a blocked read is interrupted by shutting the
+    /// TCP connection down, and no reconnection is attempted.
+    pub fn shutdown(&self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+        if let Some(ref stream) = *self.stream.lock().unwrap() {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+    }
+
+    pub fn is_shutdown(&self) -> bool {
+        self.shutdown.load(Ordering::Relaxed)
+    }
+
+    fn set_stream(&self, stream: TcpStream) {
+        let mut current = self.stream.lock().unwrap();
+        // Shut down while connecting
+        if self.is_shutdown() {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+        *current = Some(stream);
+    }
 }
 
 pub struct WebSockets<'a> {
     pub socket: Option<(WebSocket<AutoStream>, Response)>,
     handler: Box<dyn FnMut(WebsocketEvent) -> Result<()> + 'a>,
+    conf: Config,
+    // Streams of the connection, reused to reconnect
+    subscriptions: Subscriptions,
+    watchdog: Watchdog,
+    shutdown: ShutdownHandle,
 }
 
 impl<'a> WebSockets<'a> {
     pub fn new<Callback>(handler: Callback) -> WebSockets<'a>
+    where
+        Callback: FnMut(WebsocketEvent) -> Result<()> + 'a,
+    {
+        Self::new_with_config(handler, Config::default())
+    }
+
+    pub fn new_with_config<Callback>(handler: Callback, config: Config) -> WebSockets<'a>
     where
         Callback: FnMut(WebsocketEvent) -> Result<()> + 'a,
     {
         WebSockets {
             socket: None,
             handler: Box::new(handler),
+            watchdog: Watchdog::new(config.ws_heartbeat.clone()),
+            conf: config,
+            subscriptions: Subscriptions::default(),
+            shutdown: ShutdownHandle::default(),
         }
     }
 
+    // Shares the shutdown handle with the handler, created before the `WebSockets`
+    pub(crate) fn set_shutdown_handle(mut self, shutdown: ShutdownHandle) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
     pub fn connect(&mut self, endpoint: &str) -> Result<()> {
-        let wss: String = format!("{}{}", WEBSOCKET_URL, endpoint);
-        let url = Url::parse(&wss)?;
+        self.socket = Some(self.open(endpoint)?);
+        self.subscriptions = Subscriptions::new(endpoint);
+
+        (self.handler)(WebsocketEvent::Connected)
+    }
+
+    pub fn connect_streams(&mut self, streams: &[StreamName]) -> Result<()> {
+        self.connect(&StreamName::combined_endpoint(streams))
+    }
+
+    fn open(&mut self, endpoint: &str) -> Result<(WebSocket<AutoStream>, Response)> {
+        let url = combined_stream_url(&self.conf, endpoint)?;
 
         match connect(url) {
             Ok(answer) => {
-                self.socket = Some(answer);
-                Ok(())
+                let stream = match *answer.0.get_ref() {
+                    StreamSwitcher::Plain(ref stream) => stream,
+                    StreamSwitcher::Tls(ref stream) => stream.get_ref(),
+                };
+                stream.set_read_timeout(self.watchdog.read_timeout())?;
+                self.shutdown.set_stream(stream.try_clone()?);
+                self.watchdog.reset();
+                Ok(answer)
             }
             Err(e) => {
                 bail!(format!("Error during handshake {}", e));
@@ -70,6 +460,52 @@ impl<'a> WebSockets<'a> {
         }
     }
 
+    /// Adds streams to the open connection, without reconnecting.
+    ///
+    /// Returns the id of the request. The answer is passed to the handler by
+    /// `event_loop`, as `WebsocketEvent::Subscribed` or `WebsocketEvent::SubscriptionError`
+    /// with the same id.
+    ///
+    /// A reconnection only restores the streams of answered requests: a request left
+    /// unanswered by the lost connection has to be sent again after
+    /// `WebsocketEvent::Reconnected`.
+    pub fn subscribe(&mut self, streams: &[&str]) -> Result<u64> {
+        self.send_request(SUBSCRIBE, streams)
+    }
+
+    /// Removes streams from the open connection, answered with `WebsocketEvent::Unsubscribed`.
+    pub fn unsubscribe(&mut self, streams: &[&str]) -> Result<u64> {
+        self.send_request(UNSUBSCRIBE, streams)
+    }
+
+    /// Asks for the streams of the connection, answered with `WebsocketEvent::Subscriptions`.
+    pub fn list_subscriptions(&mut self) -> Result<u64> {
+        self.send_request(LIST_SUBSCRIPTIONS, &[])
+    }
+
+    /// Streams of the connection, as of the last answered request.
+    pub fn streams(&self) -> &[String] {
+        self.subscriptions.streams()
+    }
+
+    fn send_request(&mut self, method: &'static str, streams: &[&str]) -> Result<u64> {
+        let socket = match self.socket {
+            Some(ref mut socket) => socket,
+            None => bail!("Not connected"),
+        };
+        let (id, request) = self.subscriptions.request(method, streams);
+        socket.0.write_message(Message::Text(request))?;
+
+        Ok(id)
+    }
+
+    /// Handle stopping `event_loop` from another thread, even while it waits for a
+    /// message. The handle applies to the reconnections too; once shut down, the
+    /// connection can't be used again.
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown.clone()
+    }
+
     pub fn disconnect(&mut self) -> Result<()> {
         if let Some(ref mut socket) = self.socket {
             socket.0.close(None)?;
@@ -80,72 +516,539 @@ impl<'a> WebSockets<'a> {
     }
 
     pub fn event_loop(&mut self, running: &AtomicBool) -> Result<()> {
-        while running.load(Ordering::Relaxed) {
-            if let Some(ref mut socket) = self.socket {
-                let message = socket.0.read_message()?;
-
-                match message {
-                    Message::Text(msg) => {
-                        let mut stream_val: serde_json::Value = serde_json::from_str(&msg)?;
-                        match &stream_val["stream"] {
-                            serde_json::Value::String(stream_name) => {
-                                if stream_val["data"].is_object() {
-                                    if stream_name.contains("markPrice") {
-                                        let futures_funding: FuturesFunding = from_value(stream_val["data"].take()).unwrap();
-                                        (self.handler)(WebsocketEvent::FuturesFunding(futures_funding))?;
-                                    }
-                                    else {
-                                        let stream_data = stream_val["data"].as_object().unwrap();
-                                        if stream_data.get("u") != None &&
-                                            stream_data.get("s") != None &&
-                                            stream_data.get("b") != None &&
-                                            stream_data.get("B") != None &&
-                                            stream_data.get("a") != None &&
-                                            stream_data.get("A") != None
-                                        {
-                                            let book_ticker: BookTickerEvent = from_value(stream_val["data"].take()).unwrap();
-                                            (self.handler)(WebsocketEvent::BookTicker(book_ticker))?;
-                                        } else if msg.find(OUTBOUND_ACCOUNT_INFO) != None {
-                                            let account_update: AccountUpdateEvent = from_value(stream_val["data"].take()).unwrap();
-                                            (self.handler)(WebsocketEvent::AccountUpdate(account_update))?;
-                                        } else if msg.find(EXECUTION_REPORT) != None {
-                                            let order_trade: OrderTradeEvent = from_value(stream_val["data"].take()).unwrap();
-                                            (self.handler)(WebsocketEvent::OrderTrade(order_trade))?;
-                                        } else if msg.find(AGGREGATED_TRADE) != None {
-                                            let trade: TradesEvent = from_value(stream_val["data"].take()).unwrap();
-                                            (self.handler)(WebsocketEvent::Trade(trade))?;
-                                        } else if msg.find(DAYTICKER) != None {
-                                            let trades: Vec<DayTickerEvent> = from_value(stream_val["data"].take()).unwrap();
-                                            (self.handler)(WebsocketEvent::DayTicker(trades))?;
-                                        } else if msg.find(KLINE) != None {
-                                            let kline: KlineEvent = from_value(stream_val["data"].take()).unwrap();
-                                            (self.handler)(WebsocketEvent::Kline(kline))?;
-                                        } else if msg.find(PARTIAL_ORDERBOOK) != None {
-                                            let partial_orderbook: OrderBook = from_value(stream_val["data"].take()).unwrap();
-                                            (self.handler)(WebsocketEvent::OrderBook(partial_orderbook))?;
-                                        } else if msg.find(DEPTH_ORDERBOOK) != None {
-                                            let depth_orderbook: DepthOrderBookEvent = from_value(stream_val["data"].take()).unwrap();
-                                            (self.handler)(WebsocketEvent::DepthOrderBook(depth_orderbook))?;
-                                        } else if msg.find(ACCOUNT_UPDATE) != None {
-                                            let futures_account_update: FuturesAccountUpdateEvent = from_value(stream_val["data"].take()).unwrap();
-                                            (self.handler)(WebsocketEvent::FuturesAccountUpdateEvent(futures_account_update))?;
-                                        } else if msg.find(ORDER_TRADE_UPDATE) != None {
-                                            let order_trade_update: OrderTradeUpdateEvent = from_value(stream_val["data"].take()).unwrap();
-                                            (self.handler)(WebsocketEvent::OrderTradeUpdateEvent(order_trade_update))?;
-                                        }
-                                    }
-                                }
-                            },
-                            _ => (),
-                        };
+        while running.load(Ordering::Relaxed) && !self.shutdown.is_shutdown() {
+            let message = match self.socket {
+                Some(ref mut socket) => socket.0.read_message(),
+                None => continue,
+            };
+
+            if message.is_ok() {
+                self.watchdog.received();
+            } else if self.shutdown.is_shutdown() {
+                break;
+            }
+
+            match message {
+                Ok(Message::Text(msg)) => {
+                    if let Some(event) = self.subscriptions.decode(msg) {
+                        (self.handler)(event)?;
                     }
-                    Message::Ping(_) | Message::Pong(_) | Message::Binary(_) => {}
-                    Message::Close(e) => {
-                        bail!(format!("Disconnected {:?}", e));
+                }
+                Ok(Message::Ping(_)) => {
+                    // Sends the pong queued by tungstenite now rather than on the next read
+                    if let Err(e) = self.write_pending() {
+                        self.reconnect(running, e)?;
                     }
                 }
+                Ok(Message::Pong(_)) | Ok(Message::Binary(_)) => {}
+                Ok(Message::Close(e)) => {
+                    self.reconnect(running, format!("Disconnected {:?}", e).into())?;
+                    continue;
+                }
+                // Read timeout of the heartbeat, nothing was received
+                Err(tungstenite::Error::Io(ref e)) if is_timeout(e) => {}
+                Err(e) => {
+                    self.reconnect(running, e.into())?;
+                    continue;
+                }
+            }
+
+            match self.watchdog.check() {
+                Ok(true) => {
+                    if let Err(e) = self.ping() {
+                        self.reconnect(running, e)?;
+                    }
+                }
+                Ok(false) => {}
+                Err(e) => self.reconnect(running, e)?,
             }
         }
         Ok(())
     }
+
+    fn write_pending(&mut self) -> Result<()> {
+        if let Some(ref mut socket) = self.socket {
+            socket.0.write_pending()?;
+        }
+        Ok(())
+    }
+
+    fn ping(&mut self) -> Result<()> {
+        if let Some(ref mut socket) = self.socket {
+            socket.0.write_message(Message::Ping(Vec::new()))?;
+        }
+        Ok(())
+    }
+
+    // Reconnects to the same streams after the connection was lost, Binance closes
+    // every connection after 24 hours. Fails with `error` when reconnection is disabled,
+    // or with the last handshake error after `max_retries` failed attempts in a row.
+    // The handler gets `WebsocketEvent::Disconnected` either way.
+    fn reconnect(&mut self, running: &AtomicBool, error: Error) -> Result<()> {
+        (self.handler)(WebsocketEvent::Disconnected(error.to_string()))?;
+        let policy = match self.conf.ws_reconnect {
+            Some(ref policy) => policy.clone(),
+            None => return Err(error),
+        };
+        self.socket = None;
+
+        let endpoint = self.subscriptions.reconnect_endpoint();
+        let mut attempt = 0;
+        while running.load(Ordering::Relaxed) && !self.shutdown.is_shutdown() {
+            thread::sleep(policy.backoff(attempt));
+
+            match self.open(&endpoint) {
+                Ok(socket) => {
+                    self.socket = Some(socket);
+                    return (self.handler)(WebsocketEvent::Reconnected);
+                }
+                Err(e) => {
+                    attempt += 1;
+                    if attempt > policy.max_retries {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn is_timeout(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
+}
+
+/// Decodes a combined stream message into an event, by the stream name and the
+/// `e` field of the data.
+///
+/// Returns `None` for messages which are not stream data, e.g. the answers to
+/// requests, `WebsocketEvent::Unknown` for stream data of an unknown type, and
+/// `WebsocketEvent::Invalid` if the data doesn't match the schema of its type.
+pub(crate) fn parse_message(mut stream_val: serde_json::Value) -> Option<WebsocketEvent> {
+    let stream_name = match &stream_val["stream"] {
+        serde_json::Value::String(stream_name) => stream_name.clone(),
+        _ => return None,
+    };
+    let data = stream_val["data"].take();
+
+    let event = match decode_data(&stream_name, &data) {
+        Ok(Some(event)) => event,
+        Ok(None) => WebsocketEvent::Unknown(data),
+        Err(error) => WebsocketEvent::Invalid { error, data },
+    };
+
+    Some(event)
+}
+
+// Event of the stream data, `None` if its type is unknown. The data is borrowed,
+// so it is still at hand when the decoding fails.
+fn decode_data(stream_name: &str, data: &serde_json::Value) -> Result<Option<WebsocketEvent>> {
+    // `!ticker@arr`, `!miniTicker@arr` and `!markPrice@arr` send every symbol in one array
+    if data.is_array() {
+        let event_type = data[0]["e"].as_str().unwrap_or_default();
+        let event = match event_type {
+            DAYTICKER => WebsocketEvent::DayTicker(decode(event_type, data)?),
+            MINI_TICKER => WebsocketEvent::MiniTicker(decode(event_type, data)?),
+            MARK_PRICE_UPDATE => WebsocketEvent::MarkPrices(decode(event_type, data)?),
+            _ => return Ok(None),
+        };
+        return Ok(Some(event));
+    }
+
+    let event_type = match data["e"].as_str() {
+        Some(event_type) => event_type,
+        // Spot book tickers and partial depths have no event type
+        None if stream_name.ends_with(BOOK_TICKER_STREAM) => BOOK_TICKER,
+        None if data.get(PARTIAL_ORDERBOOK).is_some() => PARTIAL_ORDERBOOK,
+        None => return Ok(None),
+    };
+
+    let event = match event_type {
+        OUTBOUND_ACCOUNT_INFO => WebsocketEvent::AccountUpdate(decode(event_type, data)?),
+        EXECUTION_REPORT => WebsocketEvent::OrderTrade(decode(event_type, data)?),
+        AGGREGATED_TRADE => WebsocketEvent::Trade(decode(event_type, data)?),
+        DAYTICKER => WebsocketEvent::DayTicker(vec![decode(event_type, data)?]),
+        KLINE => WebsocketEvent::Kline(decode(event_type, data)?),
+        PARTIAL_ORDERBOOK => WebsocketEvent::OrderBook(decode(event_type, data)?),
+        DEPTH_ORDERBOOK => WebsocketEvent::DepthOrderBook(decode(event_type, data)?),
+        BOOK_TICKER => WebsocketEvent::BookTicker(decode(event_type, data)?),
+        ACCOUNT_UPDATE => WebsocketEvent::FuturesAccountUpdateEvent(decode(event_type, data)?),
+        ORDER_TRADE_UPDATE => WebsocketEvent::OrderTradeUpdateEvent(decode(event_type, data)?),
+        MARK_PRICE_UPDATE => WebsocketEvent::FuturesFunding(decode(event_type, data)?),
+        MINI_TICKER => WebsocketEvent::MiniTicker(vec![decode(event_type, data)?]),
+        CONTINUOUS_KLINE => WebsocketEvent::ContinuousKline(decode(event_type, data)?),
+        FORCE_ORDER => WebsocketEvent::Liquidation(decode(event_type, data)?),
+        COMPOSITE_INDEX => WebsocketEvent::CompositeIndex(decode(event_type, data)?),
+        MARGIN_CALL => WebsocketEvent::MarginCall(decode(event_type, data)?),
+        ACCOUNT_CONFIG_UPDATE => WebsocketEvent::AccountConfigUpdate(decode(event_type, data)?),
+        LISTEN_KEY_EXPIRED => WebsocketEvent::ListenKeyExpired(decode(event_type, data)?),
+        _ => return Ok(None),
+    };
+
+    Ok(Some(event))
+}
+
+fn decode<T: DeserializeOwned>(event_type: &str, data: &serde_json::Value) -> Result<T> {
+    match T::deserialize(data) {
+        Ok(event) => Ok(event),
+        Err(e) => Err(ErrorKind::InvalidEvent(event_type.into(), e.to_string()).into()),
+    }
+}
+
+pub(crate) fn combined_stream_url(config: &Config, endpoint: &str) -> Result<Url> {
+    let wss: String = format!(
+        "{}{}{}",
+        config.ws_endpoint, WEBSOCKET_COMBINED_STREAM, endpoint
+    );
+
+    Ok(Url::parse(&wss)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use tungstenite::handshake::server::{ErrorResponse, Request, Response as ServerResponse};
+
+    // Accepts a connection, returning the socket and the requested path. The callback
+    // signature is the one of tungstenite.
+    #[allow(clippy::result_large_err)]
+    fn accept(listener: &TcpListener) -> (WebSocket<TcpStream>, String) {
+        let (stream, _) = listener.accept().unwrap();
+        let mut path = String::new();
+        let socket = tungstenite::accept_hdr(
+            stream,
+            |request: &Request,
+             response: ServerResponse|
+             -> std::result::Result<ServerResponse, ErrorResponse> {
+                path = request.uri().to_string();
+                Ok(response)
+            },
+        )
+        .unwrap();
+
+        (socket, path)
+    }
+
+    fn read_request(socket: &mut WebSocket<TcpStream>) -> serde_json::Value {
+        match socket.read_message().unwrap() {
+            Message::Text(msg) => serde_json::from_str(&msg).unwrap(),
+            message => panic!("unexpected {:?}", message),
+        }
+    }
+
+    #[test]
+    fn subscription_answers() {
+        let mut subscriptions = Subscriptions::new("btcusdt@trade");
+        let (subscribe, _) = subscriptions.request(SUBSCRIBE, &["ethusdt@trade"]);
+        let (unsubscribe, _) = subscriptions.request(UNSUBSCRIBE, &["btcusdt@trade"]);
+
+        match subscriptions.response(&json!({ "result": null, "id": subscribe })) {
+            Some(WebsocketEvent::Subscribed { id, streams }) => {
+                assert_eq!(id, subscribe);
+                assert_eq!(streams, ["ethusdt@trade"]);
+            }
+            _ => panic!("expected Subscribed"),
+        }
+        let error = json!({ "error": { "code": 2, "msg": "Invalid request" }, "id": unsubscribe });
+        match subscriptions.response(&error) {
+            Some(WebsocketEvent::SubscriptionError {
+                id,
+                streams,
+                code,
+                msg,
+            }) => {
+                assert_eq!(id, Some(unsubscribe));
+                assert_eq!(streams, ["btcusdt@trade"]);
+                assert_eq!(code, 2);
+                assert_eq!(msg, "Invalid request");
+            }
+            _ => panic!("expected SubscriptionError"),
+        }
+        assert_eq!(subscriptions.streams(), ["btcusdt@trade", "ethusdt@trade"]);
+        // Stream data
+        let data = json!({ "stream": "btcusdt@trade", "data": {} });
+        assert!(subscriptions.response(&data).is_none());
+    }
+
+    #[test]
+    fn reconnect_replays_acknowledged_streams_only() {
+        let mut subscriptions = Subscriptions::new("btcusdt@trade");
+        let (acknowledged, _) = subscriptions.request(SUBSCRIBE, &["ethusdt@trade"]);
+        subscriptions.request(SUBSCRIBE, &["bnbusdt@trade"]);
+        subscriptions.request(UNSUBSCRIBE, &["btcusdt@trade"]);
+        subscriptions.response(&json!({ "result": null, "id": acknowledged }));
+
+        assert_eq!(
+            subscriptions.reconnect_endpoint(),
+            "btcusdt@trade/ethusdt@trade"
+        );
+        assert!(subscriptions.pending.is_empty());
+    }
+
+    #[test]
+    fn event_loop_reconnects_to_acknowledged_streams() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (paths, received_paths) = mpsc::channel();
+
+        let server = thread::spawn(move || {
+            let (mut socket, path) = accept(&listener);
+            paths.send(path).unwrap();
+
+            let request = read_request(&mut socket);
+            assert_eq!(request["method"], "SUBSCRIBE");
+            socket
+                .write_message(Message::Text(
+                    json!({ "result": null, "id": request["id"] }).to_string(),
+                ))
+                .unwrap();
+            // The second request is left unanswered by the lost connection
+            read_request(&mut socket);
+            socket.close(None).unwrap();
+            drop(socket);
+
+            let (mut socket, path) = accept(&listener);
+            paths.send(path).unwrap();
+            while socket.read_message().is_ok() {}
+        });
+
+        let config = Config::default()
+            .set_ws_endpoint(format!("ws://127.0.0.1:{}", port))
+            .set_ws_reconnect(RetryPolicy {
+                max_retries: 0,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(1),
+                multiplier: 1.0,
+            });
+        let running = AtomicBool::new(true);
+        let mut events = Vec::new();
+        {
+            let mut web_socket = WebSockets::new_with_config(
+                |event| {
+                    if let WebsocketEvent::Reconnected = event {
+                        running.store(false, Ordering::Relaxed);
+                    }
+                    events.push(event);
+                    Ok(())
+                },
+                config,
+            );
+            web_socket.connect("btcusdt@trade").unwrap();
+            let acknowledged = web_socket.subscribe(&["ethusdt@trade"]).unwrap();
+            web_socket.subscribe(&["bnbusdt@trade"]).unwrap();
+
+            web_socket.event_loop(&running).unwrap();
+            assert_eq!(web_socket.streams(), ["btcusdt@trade", "ethusdt@trade"]);
+            assert_eq!(acknowledged, 1);
+            let _ = web_socket.disconnect();
+        }
+        server.join().unwrap();
+
+        assert_eq!(
+            received_paths.recv().unwrap(),
+            "/stream?streams=btcusdt@trade"
+        );
+        assert_eq!(
+            received_paths.recv().unwrap(),
+            "/stream?streams=btcusdt@trade/ethusdt@trade"
+        );
+        assert_eq!(events.len(), 4);
+        assert!(matches!(events[0], WebsocketEvent::Connected));
+        match events[1] {
+            WebsocketEvent::Subscribed { id, ref streams } => {
+                assert_eq!(id, 1);
+                assert_eq!(streams, &["ethusdt@trade"]);
+            }
+            _ => panic!("expected Subscribed"),
+        }
+        assert!(matches!(events[2], WebsocketEvent::Disconnected(_)));
+        assert!(matches!(events[3], WebsocketEvent::Reconnected));
+    }
+
+    fn trade(symbol: &str) -> serde_json::Value {
+        json!({
+            "e": "aggTrade", "E": 123456789, "s": symbol, "a": 12345, "p": "0.001", "q": "100",
+            "f": 100, "l": 105, "T": 123456785, "m": true, "M": true
+        })
+    }
+
+    fn parse(stream: &str, data: serde_json::Value) -> Option<WebsocketEvent> {
+        parse_message(json!({ "stream": stream, "data": data }))
+    }
+
+    #[test]
+    fn events_dispatched_by_type() {
+        match parse("bnbbtc@aggTrade", trade("BNBBTC")) {
+            Some(WebsocketEvent::Trade(trade)) => assert_eq!(trade.aggregated_trade_id, 12345),
+            _ => panic!("expected Trade"),
+        }
+
+        let book_ticker = json!({ "u": 400900217, "s": "BNBUSDT", "b": "25.35", "B": "31.21", "a": "25.36", "A": "40.66" });
+        match parse("bnbusdt@bookTicker", book_ticker) {
+            Some(WebsocketEvent::BookTicker(ticker)) => assert_eq!(ticker.update_id, 400900217),
+            _ => panic!("expected BookTicker"),
+        }
+
+        let depth =
+            json!({ "lastUpdateId": 160, "bids": [["0.0024", "10"]], "asks": [["0.0026", "100"]] });
+        match parse("bnbbtc@depth5", depth) {
+            Some(WebsocketEvent::OrderBook(book)) => assert_eq!(book.last_update_id, 160),
+            _ => panic!("expected OrderBook"),
+        }
+
+        let expired = json!({ "e": "listenKeyExpired", "E": 1576653824250u64 });
+        assert!(matches!(
+            parse("listenkey", expired),
+            Some(WebsocketEvent::ListenKeyExpired(_))
+        ));
+    }
+
+    #[test]
+    fn array_streams_dispatched_by_type() {
+        let mini_ticker = json!({
+            "e": "24hrMiniTicker", "E": 123456789, "s": "BNBBTC", "c": "0.0025", "o": "0.0010",
+            "h": "0.0025", "l": "0.0010", "v": "10000", "q": "18"
+        });
+        match parse("!miniTicker@arr", json!([mini_ticker.clone(), mini_ticker])) {
+            Some(WebsocketEvent::MiniTicker(tickers)) => assert_eq!(tickers.len(), 2),
+            _ => panic!("expected MiniTicker"),
+        }
+
+        let mark_price = json!({
+            "e": "markPriceUpdate", "E": 1562305380000u64, "s": "BTCUSDT", "p": "11794.15000000",
+            "i": "11784.62659091", "P": "11784.25641265", "r": "0.00038167", "T": 1562306400000u64
+        });
+        match parse("!markPrice@arr", json!([mark_price.clone()])) {
+            Some(WebsocketEvent::MarkPrices(prices)) => assert_eq!(prices[0].symbol, "BTCUSDT"),
+            _ => panic!("expected MarkPrices"),
+        }
+        match parse("btcusdt@markPrice", mark_price) {
+            Some(WebsocketEvent::FuturesFunding(price)) => assert_eq!(price.symbol, "BTCUSDT"),
+            _ => panic!("expected FuturesFunding"),
+        }
+
+        match parse("!newEvent@arr", json!([{ "e": "newEvent" }])) {
+            Some(WebsocketEvent::Unknown(data)) => assert!(data.is_array()),
+            _ => panic!("expected Unknown"),
+        }
+    }
+
+    #[test]
+    fn unknown_event_types_kept() {
+        let data = json!({ "e": "newEvent", "E": 1 });
+        match parse("btcusdt@new", data.clone()) {
+            Some(WebsocketEvent::Unknown(unknown)) => assert_eq!(unknown, data),
+            _ => panic!("expected Unknown"),
+        }
+        // No event type
+        assert!(matches!(
+            parse("btcusdt@new", json!({ "x": 1 })),
+            Some(WebsocketEvent::Unknown(_))
+        ));
+        // Not stream data
+        assert!(parse_message(json!({ "result": null, "id": 1 })).is_none());
+    }
+
+    #[test]
+    fn invalid_event_rejected() {
+        let data = json!({ "e": "aggTrade", "s": "BNBBTC" });
+        match parse("bnbbtc@aggTrade", data.clone()) {
+            Some(WebsocketEvent::Invalid {
+                error: Error(ErrorKind::InvalidEvent(event_type, _), _),
+                data: invalid,
+            }) => {
+                assert_eq!(event_type, "aggTrade");
+                assert_eq!(invalid, data);
+            }
+            _ => panic!("expected Invalid"),
+        }
+    }
+
+    #[test]
+    fn event_loop_continues_after_undecodable_messages() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = thread::spawn(move || {
+            let (mut socket, _) = accept(&listener);
+            let invalid = json!({ "stream": "bnbbtc@aggTrade", "data": { "e": "aggTrade" } });
+            let valid = json!({ "stream": "bnbbtc@aggTrade", "data": trade("BNBBTC") });
+            for message in &[
+                "not json".to_string(),
+                invalid.to_string(),
+                valid.to_string(),
+            ] {
+                socket
+                    .write_message(Message::Text(message.clone()))
+                    .unwrap();
+            }
+            while socket.read_message().is_ok() {}
+        });
+
+        let config = Config::default().set_ws_endpoint(format!("ws://127.0.0.1:{}", port));
+        let running = AtomicBool::new(true);
+        let mut events = Vec::new();
+        {
+            let mut web_socket = WebSockets::new_with_config(
+                |event| {
+                    if let WebsocketEvent::Trade(_) = event {
+                        running.store(false, Ordering::Relaxed);
+                    }
+                    events.push(event);
+                    Ok(())
+                },
+                config,
+            );
+            web_socket.connect("bnbbtc@aggTrade").unwrap();
+            web_socket.event_loop(&running).unwrap();
+            web_socket.disconnect().unwrap();
+        }
+        server.join().unwrap();
+
+        assert_eq!(events.len(), 4);
+        match events[1] {
+            WebsocketEvent::Unknown(ref message) => assert_eq!(message, "not json"),
+            _ => panic!("expected Unknown"),
+        }
+        match events[2] {
+            WebsocketEvent::Invalid { ref data, .. } => assert_eq!(data["e"], "aggTrade"),
+            _ => panic!("expected Invalid"),
+        }
+        assert!(matches!(events[3], WebsocketEvent::Trade(_)));
+    }
+
+    #[test]
+    fn connection_loss_reported_without_reconnection() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = thread::spawn(move || {
+            let (mut socket, _) = accept(&listener);
+            socket.close(None).unwrap();
+        });
+
+        let config = Config::default().set_ws_endpoint(format!("ws://127.0.0.1:{}", port));
+        let running = AtomicBool::new(true);
+        let mut events = Vec::new();
+        {
+            let mut web_socket = WebSockets::new_with_config(
+                |event| {
+                    events.push(event);
+                    Ok(())
+                },
+                config,
+            );
+            web_socket.connect("bnbbtc@aggTrade").unwrap();
+            assert!(web_socket.event_loop(&running).is_err());
+        }
+        server.join().unwrap();
+
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[1], WebsocketEvent::Disconnected(_)));
+    }
 }
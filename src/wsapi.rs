@@ -0,0 +1,655 @@
+use crate::account::NewOrder;
+use crate::client::Client;
+use crate::config::{Config, RetryPolicy};
+use crate::errors::*;
+use crate::model::*;
+use crate::util::get_timestamp;
+use crate::websockets::is_timeout;
+use serde::de::DeserializeOwned;
+use serde_json::{from_value, json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::thread;
+use std::time::{Duration, Instant};
+use tungstenite::client::AutoStream;
+use tungstenite::protocol::WebSocket;
+use tungstenite::stream::Stream as StreamSwitcher;
+use tungstenite::{connect, Message};
+use url::Url;
+
+// WebSocket API methods
+const ORDER_PLACE: &str = "order.place";
+const ORDER_CANCEL: &str = "order.cancel";
+const ORDER_STATUS: &str = "order.status";
+const ACCOUNT_BALANCE: &str = "account.balance";
+const ACCOUNT_POSITION: &str = "account.position";
+
+// Response timeout without `Config::timeout`
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+// Responses kept for `wait_response`, the oldest are dropped first
+const MAX_RESPONSES: usize = 1000;
+
+/// Client of the futures WebSocket API (`ws-fapi`), which places and queries orders
+/// over one connection with a lower latency than the REST calls of `Account`.
+///
+/// Requests are signed like the REST requests, by the signer of the `Client`. Every
+/// request carries an id: `send_signed` and `wait_response` allow several requests in
+/// flight, the responses being matched to their request by id.
+///
+/// The server pings the connection every few minutes and closes it when the pings are
+/// left unanswered for 10 minutes. They are answered while a request waits; call `poll`
+/// regularly when the client is idle.
+pub struct WsApiClient {
+    client: Client,
+    recv_window: u64,
+    endpoint: String,
+    socket: WebSocket<AutoStream>,
+    next_id: u64,
+    // Requests sent and not waited for yet
+    pending: BTreeSet<u64>,
+    // Responses of pending requests read while waiting for another one
+    responses: BTreeMap<u64, Value>,
+    timeout: Duration,
+    reconnect: Option<RetryPolicy>,
+}
+
+// What `read` got from the socket
+enum Received {
+    Nothing,
+    Message,
+    // Response without id, to a request the server couldn't read
+    Unmatched(Value),
+}
+
+impl WsApiClient {
+    /// Connects to `Config::ws_api_endpoint`, signing with the HMAC secret key.
+    pub fn connect(
+        api_key: Option<String>, secret_key: Option<String>, config: &Config,
+    ) -> Result<WsApiClient> {
+        let client = Client::try_new_with_config(
+            api_key,
+            secret_key,
+            config.futures_rest_api_endpoint.clone(),
+            config,
+        )?;
+
+        Self::connect_with_client(&client, config)
+    }
+
+    /// Connects with the credentials and the signer of an existing client, e.g. one
+    /// with an `Ed25519Signer`.
+    ///
+    /// A lost connection is reconnected with the backoff of `Config::ws_reconnect`,
+    /// when set.
+    pub fn connect_with_client(client: &Client, config: &Config) -> Result<WsApiClient> {
+        Ok(WsApiClient {
+            client: client.clone(),
+            recv_window: config.recv_window,
+            endpoint: config.ws_api_endpoint.clone(),
+            socket: open(&config.ws_api_endpoint)?,
+            next_id: 1,
+            pending: BTreeSet::new(),
+            responses: BTreeMap::new(),
+            timeout: config
+                .timeout
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_TIMEOUT),
+            reconnect: config.ws_reconnect.clone(),
+        })
+    }
+
+    /// How long `wait_response` waits for a response, `Config::timeout` or 10 seconds
+    /// by default.
+    pub fn set_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Place an order of any type, with the same parameters as `Account::place_order`
+    pub fn place_order(&mut self, order: &NewOrder) -> Result<FuturesTransaction> {
+        self.request(ORDER_PLACE, order.to_parameters())
+    }
+
+    pub fn cancel_order<S>(&mut self, symbol: S, order_id: u64) -> Result<OrderCanceled>
+    where
+        S: Into<String>,
+    {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        parameters.insert("symbol".into(), symbol.into());
+        parameters.insert("orderId".into(), order_id.to_string());
+
+        self.request(ORDER_CANCEL, parameters)
+    }
+
+    // Check an order's status
+    pub fn order_status<S>(&mut self, symbol: S, order_id: u64) -> Result<FuturesOrder>
+    where
+        S: Into<String>,
+    {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        parameters.insert("symbol".into(), symbol.into());
+        parameters.insert("orderId".into(), order_id.to_string());
+
+        self.request(ORDER_STATUS, parameters)
+    }
+
+    // Balances of every asset
+    pub fn get_balances(&mut self) -> Result<Vec<FuturesAssetBalance>> {
+        self.request(ACCOUNT_BALANCE, BTreeMap::new())
+    }
+
+    // Positions of ONE symbol
+    pub fn get_positions<S>(&mut self, symbol: S) -> Result<Vec<FuturesPositionV2>>
+    where
+        S: Into<String>,
+    {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        parameters.insert("symbol".into(), symbol.into());
+
+        self.request(ACCOUNT_POSITION, parameters)
+    }
+
+    /// Sends a signed request and waits for its result.
+    pub fn request<T: DeserializeOwned>(
+        &mut self, method: &str, parameters: BTreeMap<String, String>,
+    ) -> Result<T> {
+        let id = self.send_signed(method, parameters)?;
+
+        self.wait_response(id)
+    }
+
+    /// Sends a signed request without waiting, returns the id to pass to `wait_response`.
+    pub fn send_signed(
+        &mut self, method: &str, mut parameters: BTreeMap<String, String>,
+    ) -> Result<u64> {
+        if self.recv_window > 0 {
+            parameters.insert("recvWindow".into(), self.recv_window.to_string());
+        }
+        let parameters = self.client.sign_parameters(parameters)?;
+
+        let id = self.next_id;
+        self.next_id += 1;
+        let request = json!({
+            "id": id,
+            "method": method,
+            "params": parameters,
+        });
+        self.socket
+            .write_message(Message::Text(request.to_string()))?;
+        self.pending.insert(id);
+
+        Ok(id)
+    }
+
+    /// Waits for the response of the request `id`, keeping the responses of the other
+    /// pending requests read meanwhile.
+    ///
+    /// Fails with `ErrorKind::BinanceError` when the request was rejected. After a
+    /// timeout, or once the connection was lost, the response is not kept anymore.
+    /// An error response without id fails the call, but `id` is still pending.
+    pub fn wait_response<T: DeserializeOwned>(&mut self, id: u64) -> Result<T> {
+        let deadline = Instant::now() + self.timeout;
+
+        loop {
+            if let Some(response) = self.responses.remove(&id) {
+                self.pending.remove(&id);
+                return result(response);
+            }
+            if !self.pending.contains(&id) {
+                bail!(format!("No pending request {}", id));
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                self.pending.remove(&id);
+                bail!(format!("No response to the request {}", id));
+            }
+
+            match self.read(deadline - now) {
+                // Maybe not the answer to `id`, which stays pending
+                Ok(Received::Unmatched(response)) => {
+                    let error = &response["error"];
+                    bail!(format!(
+                        "Unmatched error response: {} {}",
+                        error["code"],
+                        error["msg"].as_str().unwrap_or_default()
+                    ));
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    self.reconnect(e)?;
+                    bail!(format!("Connection lost before the response to the request {}", id));
+                }
+            }
+        }
+    }
+
+    /// Answers the pings and reads the responses already received, without waiting.
+    /// A lost connection is reconnected when `Config::ws_reconnect` is set.
+    pub fn poll(&mut self) -> Result<()> {
+        loop {
+            match self.read(Duration::from_millis(1)) {
+                Ok(Received::Nothing) => return Ok(()),
+                Ok(_) => {}
+                Err(e) => return self.reconnect(e),
+            }
+        }
+    }
+
+    pub fn disconnect(&mut self) -> Result<()> {
+        self.socket.close(None)?;
+
+        Ok(())
+    }
+
+    // Reads one message, waiting up to `timeout`. Fails when the connection was lost.
+    fn read(&mut self, timeout: Duration) -> Result<Received> {
+        self.set_read_timeout(timeout)?;
+
+        match self.socket.read_message() {
+            Ok(Message::Text(msg)) => {
+                let response: Value = match serde_json::from_str(&msg) {
+                    Ok(response) => response,
+                    Err(_) => return Ok(Received::Message),
+                };
+                match response["id"].as_u64() {
+                    Some(id) if self.pending.contains(&id) => {
+                        self.responses.insert(id, response);
+                        if self.responses.len() > MAX_RESPONSES {
+                            let oldest = *self.responses.keys().next().unwrap();
+                            self.responses.remove(&oldest);
+                            self.pending.remove(&oldest);
+                        }
+                    }
+                    // Nobody waits for it anymore
+                    Some(_) => {}
+                    None => return Ok(Received::Unmatched(response)),
+                }
+            }
+            // The pong queued by tungstenite is sent now rather than on the next write
+            Ok(Message::Ping(_)) => self.socket.write_pending()?,
+            Ok(Message::Pong(_)) | Ok(Message::Binary(_)) => {}
+            Ok(Message::Close(e)) => bail!(format!("Disconnected {:?}", e)),
+            Err(tungstenite::Error::Io(ref e)) if is_timeout(e) => return Ok(Received::Nothing),
+            Err(e) => return Err(e.into()),
+        }
+
+        Ok(Received::Message)
+    }
+
+    // Connects again after the connection was lost, the requests in flight are not
+    // answered anymore. Fails with `error` when reconnection is disabled, or with the
+    // last handshake error after `max_retries` failed attempts in a row.
+    fn reconnect(&mut self, error: Error) -> Result<()> {
+        let policy = match self.reconnect {
+            Some(ref policy) => policy.clone(),
+            None => return Err(error),
+        };
+        self.pending.clear();
+        self.responses.clear();
+
+        let mut attempt = 0;
+        loop {
+            thread::sleep(policy.backoff(attempt));
+
+            match open(&self.endpoint) {
+                Ok(socket) => {
+                    self.socket = socket;
+                    return Ok(());
+                }
+                Err(e) => {
+                    attempt += 1;
+                    if attempt > policy.max_retries {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+    }
+
+    fn set_read_timeout(&self, timeout: Duration) -> Result<()> {
+        let stream = match *self.socket.get_ref() {
+            StreamSwitcher::Plain(ref stream) => stream,
+            StreamSwitcher::Tls(ref stream) => stream.get_ref(),
+        };
+        stream.set_read_timeout(Some(timeout.max(Duration::from_millis(1))))?;
+
+        Ok(())
+    }
+}
+
+fn open(endpoint: &str) -> Result<WebSocket<AutoStream>> {
+    let url = Url::parse(endpoint)?;
+
+    match connect(url) {
+        Ok((socket, _)) => Ok(socket),
+        Err(e) => bail!(format!("Error during handshake {}", e)),
+    }
+}
+
+// Result of a response, `{"id", "status", "result"}` or `{"id", "status", "error"}`
+fn result<T: DeserializeOwned>(mut response: Value) -> Result<T> {
+    match response["status"].as_u64() {
+        Some(200) => Ok(from_value(response["result"].take())?),
+        Some(status @ 429) | Some(status @ 418) => {
+            // Timestamp (ms) when the ban or the limit ends
+            let retry_after = match (
+                response["error"]["data"]["retryAfter"].as_u64(),
+                get_timestamp(),
+            ) {
+                (Some(until), Ok(now)) => Some(Duration::from_millis(until.saturating_sub(now))),
+                _ => None,
+            };
+            if status == 429 {
+                Err(ErrorKind::TooManyRequests(retry_after).into())
+            } else {
+                Err(ErrorKind::IpBanned(retry_after).into())
+            }
+        }
+        _ => {
+            let error: BinanceContentError = from_value(response["error"].take())?;
+
+            Err(ErrorKind::BinanceError(error).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::mpsc;
+    use std::thread::JoinHandle;
+
+    // Local server running `serve` on each accepted connection
+    fn server<F>(connections: usize, serve: F) -> (Config, JoinHandle<()>)
+    where
+        F: Fn(usize, &mut WebSocket<TcpStream>) + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = thread::spawn(move || {
+            for connection in 0..connections {
+                let (stream, _) = listener.accept().unwrap();
+                let mut socket = tungstenite::accept(stream).unwrap();
+                serve(connection, &mut socket);
+            }
+        });
+        let config = Config::default().set_ws_api_endpoint(format!("ws://127.0.0.1:{}", port));
+
+        (config, server)
+    }
+
+    fn client(config: &Config) -> WsApiClient {
+        WsApiClient::connect(Some("key".into()), Some("secret".into()), config).unwrap()
+    }
+
+    fn read_request(socket: &mut WebSocket<TcpStream>) -> Value {
+        match socket.read_message().unwrap() {
+            Message::Text(msg) => serde_json::from_str(&msg).unwrap(),
+            message => panic!("unexpected {:?}", message),
+        }
+    }
+
+    fn respond(socket: &mut WebSocket<TcpStream>, id: &Value, result: Value) {
+        let response = json!({ "id": id, "status": 200, "result": result });
+        socket
+            .write_message(Message::Text(response.to_string()))
+            .unwrap();
+    }
+
+    fn closed(socket: &mut WebSocket<TcpStream>) {
+        while socket.read_message().is_ok() {}
+    }
+
+    #[test]
+    fn responses_matched_by_id() {
+        let (config, server) = server(1, |_, socket| {
+            let first = read_request(socket);
+            let second = read_request(socket);
+            assert_eq!(first["method"], ORDER_STATUS);
+            assert_eq!(first["params"]["symbol"], "BTCUSDT");
+            assert_eq!(first["params"]["apiKey"], "key");
+            assert!(first["params"]["signature"].is_string());
+            assert!(first["params"]["timestamp"].is_string());
+
+            respond(socket, &second["id"], json!({ "request": 2 }));
+            respond(socket, &first["id"], json!({ "request": 1 }));
+            closed(socket);
+        });
+        let mut client = client(&config);
+
+        let mut parameters = BTreeMap::new();
+        parameters.insert("symbol".to_string(), "BTCUSDT".to_string());
+        let first = client.send_signed(ORDER_STATUS, parameters).unwrap();
+        let second = client
+            .send_signed(ACCOUNT_BALANCE, BTreeMap::new())
+            .unwrap();
+
+        let response: Value = client.wait_response(first).unwrap();
+        assert_eq!(response["request"], 1);
+        assert!(client.responses.contains_key(&second));
+        let response: Value = client.wait_response(second).unwrap();
+        assert_eq!(response["request"], 2);
+        assert!(client.responses.is_empty() && client.pending.is_empty());
+
+        client.disconnect().unwrap();
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn unmatched_error_keeps_request_pending() {
+        let (config, server) = server(1, |_, socket| {
+            let request = read_request(socket);
+            let error = json!({ "code": -1000, "msg": "Unreadable request" });
+            let response = json!({ "id": null, "status": 400, "error": error });
+            socket
+                .write_message(Message::Text(response.to_string()))
+                .unwrap();
+            respond(socket, &request["id"], json!({ "request": 1 }));
+            closed(socket);
+        });
+        let mut client = client(&config);
+
+        let id = client
+            .send_signed(ACCOUNT_BALANCE, BTreeMap::new())
+            .unwrap();
+        match client.wait_response::<Value>(id) {
+            Err(e) => assert_eq!(
+                e.to_string(),
+                "Unmatched error response: -1000 Unreadable request"
+            ),
+            Ok(_) => panic!("expected an error"),
+        }
+        assert!(client.pending.contains(&id));
+        let response: Value = client.wait_response(id).unwrap();
+        assert_eq!(response["request"], 1);
+
+        client.disconnect().unwrap();
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn pings_answered_while_waiting() {
+        let (config, server) = server(1, |_, socket| {
+            let request = read_request(socket);
+            socket
+                .write_message(Message::Ping(b"ping".to_vec()))
+                .unwrap();
+            // Responds once the ping was answered
+            match socket.read_message().unwrap() {
+                Message::Pong(payload) => assert_eq!(payload, b"ping"),
+                message => panic!("unexpected {:?}", message),
+            }
+            respond(socket, &request["id"], json!([]));
+            closed(socket);
+        });
+        let mut client = client(&config);
+
+        let balances: Vec<FuturesAssetBalance> =
+            client.request(ACCOUNT_BALANCE, BTreeMap::new()).unwrap();
+        assert!(balances.is_empty());
+
+        client.disconnect().unwrap();
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn poll_answers_pings() {
+        let (answered, pong) = mpsc::channel();
+        let (config, server) = server(1, move |_, socket| {
+            socket.write_message(Message::Ping(Vec::new())).unwrap();
+            if let Message::Pong(_) = socket.read_message().unwrap() {
+                answered.send(()).unwrap();
+            }
+            closed(socket);
+        });
+        let mut client = client(&config);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while pong.try_recv().is_err() {
+            assert!(Instant::now() < deadline, "ping not answered");
+            client.poll().unwrap();
+        }
+
+        client.disconnect().unwrap();
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn late_responses_dropped() {
+        let (answered, pong) = mpsc::channel();
+        let (timed_out, wait_timeout) = mpsc::channel::<()>();
+        let (config, server) = server(1, move |_, socket| {
+            let request = read_request(socket);
+            wait_timeout.recv().unwrap();
+            respond(socket, &request["id"], json!({}));
+            // Read after the response
+            socket.write_message(Message::Ping(Vec::new())).unwrap();
+            if let Message::Pong(_) = socket.read_message().unwrap() {
+                answered.send(()).unwrap();
+            }
+            closed(socket);
+        });
+        let mut client = client(&config).set_timeout(Duration::from_millis(50));
+
+        let id = client
+            .send_signed(ACCOUNT_BALANCE, BTreeMap::new())
+            .unwrap();
+        let started = Instant::now();
+        assert!(client.wait_response::<Value>(id).is_err());
+        assert!(started.elapsed() >= Duration::from_millis(50));
+        timed_out.send(()).unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while pong.try_recv().is_err() {
+            assert!(Instant::now() < deadline, "ping not answered");
+            client.poll().unwrap();
+        }
+        assert!(client.responses.is_empty() && client.pending.is_empty());
+        assert!(client.wait_response::<Value>(id).is_err());
+
+        client.disconnect().unwrap();
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn timeout_from_config() {
+        let (config, server) = server(1, |_, socket| closed(socket));
+        let mut client = client(&config.set_timeout(Some(3)));
+        assert_eq!(client.timeout, Duration::from_secs(3));
+
+        client.disconnect().unwrap();
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn poll_reconnects() {
+        let (reconnected, wait_reconnected) = mpsc::channel();
+        let (config, server) = server(2, move |connection, socket| {
+            if connection == 0 {
+                socket.close(None).unwrap();
+                return;
+            }
+            reconnected.send(()).unwrap();
+            let request = read_request(socket);
+            respond(socket, &request["id"], json!({ "connection": connection }));
+            closed(socket);
+        });
+        let config = config.set_ws_reconnect(RetryPolicy {
+            max_retries: 0,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+            multiplier: 1.0,
+        });
+        let mut client = client(&config);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while wait_reconnected.try_recv().is_err() {
+            assert!(Instant::now() < deadline, "not reconnected");
+            client.poll().unwrap();
+        }
+        let response: Value = client.request(ACCOUNT_BALANCE, BTreeMap::new()).unwrap();
+        assert_eq!(response["connection"], 1);
+
+        client.disconnect().unwrap();
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn connection_loss_fails_without_reconnect_policy() {
+        let (config, server) = server(1, |_, socket| {
+            read_request(socket);
+            socket.close(None).unwrap();
+        });
+        let mut client = client(&config);
+
+        assert!(client
+            .request::<Value>(ACCOUNT_BALANCE, BTreeMap::new())
+            .is_err());
+        server.join().unwrap();
+    }
+
+    fn error_response(status: u64, error: Value) -> Value {
+        json!({ "id": 1, "status": status, "error": error })
+    }
+
+    #[test]
+    fn errors_mapped() {
+        let rejected = error_response(
+            400,
+            json!({ "code": -2013, "msg": "Order does not exist." }),
+        );
+        match result::<Value>(rejected) {
+            Err(Error(ErrorKind::BinanceError(error), _)) => {
+                assert_eq!(error.code, -2013);
+                assert_eq!(error.msg, "Order does not exist.");
+            }
+            _ => panic!("expected BinanceError"),
+        }
+
+        let retry_after = get_timestamp().unwrap() + 60_000;
+        let data = json!({ "retryAfter": retry_after });
+        let limited = error_response(
+            429,
+            json!({ "code": -1003, "msg": "Too many requests", "data": data }),
+        );
+        match result::<Value>(limited) {
+            Err(Error(ErrorKind::TooManyRequests(Some(wait)), _)) => {
+                assert!(wait <= Duration::from_secs(60) && wait > Duration::from_secs(50))
+            }
+            _ => panic!("expected TooManyRequests"),
+        }
+
+        let banned = error_response(
+            418,
+            json!({ "code": -1003, "msg": "Way too many requests" }),
+        );
+        assert!(matches!(
+            result::<Value>(banned),
+            Err(Error(ErrorKind::IpBanned(None), _))
+        ));
+
+        let answered = json!({ "id": 1, "status": 200, "result": { "orderId": 1 } });
+        assert_eq!(result::<Value>(answered).unwrap()["orderId"], 1);
+    }
+}